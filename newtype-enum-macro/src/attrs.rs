@@ -0,0 +1,169 @@
+//! Routing of attributes between the enum and the generated structs.
+
+use crate::NewtypeEnum;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    meta::ParseNestedMeta, parenthesized, parse_quote, punctuated::Punctuated, Attribute, Error,
+    Meta, Path, Token,
+};
+
+/// Attributes of the enum, which are never copied to the generated structs.
+const ENUM_ONLY: &[&str] = &["doc", "repr", "non_exhaustive"];
+
+/// The explicitly routed attributes of the `enum_attrs`, `variant_attrs` and `derive_variants` arguments.
+#[derive(Default)]
+pub struct Routing {
+    enum_attrs: Vec<Meta>,
+    variant_attrs: Vec<Meta>,
+}
+
+impl Routing {
+    /// Parse a routing argument of the `#[newtype_enum]` attribute.
+    ///
+    /// Returns `false` if the argument is not a routing argument.
+    pub fn parse(&mut self, meta: &ParseNestedMeta) -> Result<bool, Error> {
+        let attrs = if meta.path.is_ident("enum_attrs") {
+            &mut self.enum_attrs
+        } else if meta.path.is_ident("variant_attrs") || meta.path.is_ident("derive_variants") {
+            &mut self.variant_attrs
+        } else {
+            return Ok(false);
+        };
+        let content;
+        parenthesized!(content in meta.input);
+        if meta.path.is_ident("derive_variants") {
+            let derives: TokenStream = content.parse()?;
+            attrs.push(parse_quote!(derive(#derives)));
+        } else {
+            attrs.extend(Punctuated::<Meta, Token![,]>::parse_terminated(&content)?);
+        }
+        Ok(true)
+    }
+
+    /// Get the attributes of the enum: its own attributes followed by the `enum_attrs`.
+    pub fn enum_attrs(&self, attrs: &[Attribute]) -> Vec<Attribute> {
+        let routed = self.enum_attrs.iter().map(|meta| parse_quote!(#[#meta]));
+        attrs.iter().cloned().chain(routed).collect()
+    }
+
+    /// Get the attributes of the generated structs: the copied attributes of the enum and the variant followed by the `variant_attrs`.
+    ///
+    /// `#[derive(Default)]` is only added to the struct of the `#[default]` variant (`default`), because the
+    /// other structs may have fields without a default value.
+    pub fn struct_attrs(
+        &self,
+        enum_attrs: &[Attribute],
+        var_attrs: &[Attribute],
+        default: Option<Path>,
+    ) -> Vec<Attribute> {
+        let enum_attrs = enum_attrs
+            .iter()
+            .filter(|attr| !ENUM_ONLY.iter().any(|name| attr.path().is_ident(name)))
+            .cloned()
+            .collect();
+        let default = default.map(|path| parse_quote!(#[derive(#path)]));
+        let variant_attrs = self.variant_attrs.iter().map(|meta| parse_quote!(#[#meta]));
+        without_derive_default(enum_attrs)
+            .into_iter()
+            .chain(default)
+            .chain(var_attrs.iter().cloned())
+            .chain(variant_attrs)
+            .collect()
+    }
+}
+
+/// Parse the derive macros of a `#[derive]` attribute.
+fn derives(attr: &Attribute) -> Vec<Path> {
+    if !attr.path().is_ident("derive") {
+        return Vec::new();
+    }
+    attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+        .map(|paths| paths.into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Remove `Default` from the `#[derive]` attributes.
+///
+/// All variants of the transformed enum have fields, so `#[default]` cannot be used on the enum
+/// and `Default` is implemented by `implement_default` instead.
+pub fn without_derive_default(attrs: Vec<Attribute>) -> Vec<Attribute> {
+    attrs
+        .into_iter()
+        .filter_map(|attr| {
+            let paths = derives(&attr);
+            if !paths.iter().any(is_default) {
+                return Some(attr);
+            }
+            let paths: Vec<_> = paths.into_iter().filter(|path| !is_default(path)).collect();
+            (!paths.is_empty()).then(|| parse_quote!(#[derive(#(#paths),*)]))
+        })
+        .collect()
+}
+
+fn is_default(path: &Path) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Default")
+}
+
+impl NewtypeEnum {
+    /// Get the attributes of the enum (including the `enum_attrs`).
+    pub(crate) fn enum_attrs(&self) -> Vec<Attribute> {
+        self.routing.enum_attrs(&self.item.attrs)
+    }
+
+    /// Get the `Default` path of the `#[derive]` attributes of the enum.
+    pub(crate) fn derive_default(&self) -> Option<Path> {
+        self.enum_attrs().iter().flat_map(derives).find(is_default)
+    }
+
+    /// Check that `#[derive(Default)]` and a `#[default]` variant are used together.
+    pub(crate) fn check_default(&self) -> Result<(), Error> {
+        let mut defaults = self
+            .item
+            .variants
+            .iter()
+            .zip(&self.options)
+            .filter(|(_, options)| options.default);
+        match (self.derive_default(), defaults.next(), defaults.next()) {
+            (Some(path), None, _) => Err(Error::new_spanned(
+                path,
+                "`#[derive(Default)]` on a newtype enum requires a `#[default]` variant",
+            )),
+            (None, Some((var, _)), _) => Err(Error::new_spanned(
+                &var.ident,
+                "a `#[default]` variant requires `#[derive(Default)]` on the enum",
+            )),
+            (_, _, Some((var, _))) => Err(Error::new_spanned(
+                &var.ident,
+                "multiple `#[default]` variants",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Implement `Default` for the enum with the default value of the `#[default]` variant.
+    pub(crate) fn implement_default(&self) -> TokenStream {
+        let mut vars = self.variants().zip(&self.options);
+        let Some((var, _)) = vars.find(|(_, options)| options.default) else {
+            return TokenStream::new();
+        };
+        let e = &self.item.ident;
+        let ident = &var.ident;
+        let ty = &var.fields.iter().next().unwrap().ty;
+        let mut generics = self.item.generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: ::core::default::Default));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::core::default::Default for #e #ty_generics #where_clause {
+                fn default() -> Self {
+                    #e::#ident(::core::default::Default::default())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Borrowed views of newtype variants (`VariantRef` implementations).
+
+use crate::{NewtypeEnum, UsedGenerics};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashMap;
+use syn::{
+    parse_quote, visit::Visit, Error, GenericArgument, GenericParam, Path, PathArguments, Type,
+};
+
+/// The standard library types with a view, which are in the prelude and can be named without a path.
+const PRELUDE: &[&str] = &["String", "Vec", "Box"];
+
+/// The modules of the standard library types with a view (relative to `std` or `alloc`).
+const MODULES: &[(&str, &str)] = &[
+    ("String", "string"),
+    ("Vec", "vec"),
+    ("Box", "boxed"),
+    ("Rc", "rc"),
+    ("Arc", "sync"),
+    ("Cow", "borrow"),
+    ("PathBuf", "path"),
+    ("OsString", "ffi"),
+    ("CString", "ffi"),
+];
+
+/// Check if a path names a standard library type: either a prelude type without a path
+/// or a type with its full path in `std` or `alloc` (e.g. `std::path::PathBuf`).
+///
+/// Other paths are not resolved, so a type with the same name from another crate is not mistaken for it.
+fn is_std_path(path: &Path) -> bool {
+    let segments: Vec<_> = path.segments.iter().collect();
+    let Some((last, modules)) = segments.split_last() else {
+        return false;
+    };
+    let name = last.ident.to_string();
+    match modules {
+        [] => path.leading_colon.is_none() && PRELUDE.contains(&name.as_str()),
+        [krate, module] => {
+            (krate.ident == "std" || krate.ident == "alloc")
+                && krate.arguments.is_none()
+                && module.arguments.is_none()
+                && MODULES.contains(&(name.as_str(), module.ident.to_string().as_str()))
+        }
+        _ => false,
+    }
+}
+
+/// Get the view of a well-known owned type from the standard library, which implements `Borrow`.
+fn std_view(ty: &Type) -> Option<Type> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+    if ty.qself.is_some() || !is_std_path(&ty.path) {
+        return None;
+    }
+    let segment = ty.path.segments.last()?;
+    let args: Vec<_> = match &segment.arguments {
+        PathArguments::None => Vec::new(),
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        PathArguments::Parenthesized(_) => return None,
+    };
+    match (segment.ident.to_string().as_str(), args.as_slice()) {
+        ("String", []) => Some(parse_quote!(str)),
+        ("PathBuf", []) => Some(parse_quote!(::std::path::Path)),
+        ("OsString", []) => Some(parse_quote!(::std::ffi::OsStr)),
+        ("CString", []) => Some(parse_quote!(::core::ffi::CStr)),
+        ("Vec", [ty]) => Some(parse_quote!([#ty])),
+        ("Box" | "Rc" | "Arc" | "Cow", [ty]) => Some((*ty).clone()),
+        _ => None,
+    }
+}
+
+impl NewtypeEnum {
+    /// Collect the views of all variants with a `Variant` implementation.
+    ///
+    /// Explicit views (`#[newtype_enum(borrow = "...")]`) must be unique. Views of well-known types
+    /// are skipped, if they are used by another variant, are the type of another variant or use a type parameter of the enum
+    /// (the `VariantRef` implementation would violate the orphan rules) or if the variant has the
+    /// `#[newtype_enum(no_borrow)]` argument.
+    fn views(&self) -> Result<Vec<(Type, Type)>, Error> {
+        let mut views = Vec::new();
+        let mut explicit = HashMap::new();
+        let vars = self.variants().zip(&self.item.variants).zip(&self.options);
+        for ((var, orig), options) in vars.filter(|(_, options)| !options.skip) {
+            let ty = var.fields.into_iter().next().unwrap().ty;
+            if let Some(view) = &options.borrow {
+                if let Some(other) = explicit.insert(quote!(#view).to_string(), orig.ident.clone())
+                {
+                    return Err(Error::new_spanned(
+                        view,
+                        format!(
+                            "the borrowed type `{}` is already used by the variant `{other}`",
+                            quote!(#view),
+                        ),
+                    ));
+                }
+                views.push((view.clone(), ty, true));
+            } else if !options.no_borrow && self.is_newtype(orig, options) {
+                if let Some(view) = std_view(&ty) {
+                    let mut used = UsedGenerics::new(&self.item.generics);
+                    used.visit_type(&view);
+                    let generic = self.item.generics.params.iter().any(|param| {
+                        !matches!(param, GenericParam::Lifetime(_)) && used.contains(param)
+                    });
+                    if !generic {
+                        views.push((view, ty, false));
+                    }
+                }
+            }
+        }
+
+        let mut count = HashMap::<_, usize>::new();
+        for (view, _, _) in &views {
+            *count.entry(quote!(#view).to_string()).or_default() += 1;
+        }
+        for var in self.variants() {
+            let ty = &var.fields.iter().next().unwrap().ty;
+            if let Some(count) = count.get_mut(&quote!(#ty).to_string()) {
+                *count += 1;
+            }
+        }
+        Ok(views
+            .into_iter()
+            .filter(|(view, _, explicit)| *explicit || count[&quote!(#view).to_string()] == 1)
+            .map(|(view, ty, _)| (view, ty))
+            .collect())
+    }
+
+    /// Implement `VariantRef` for the views of the variants.
+    pub(crate) fn implement_views(&self) -> Result<TokenStream, Error> {
+        let e = &self.item.ident;
+        let crate_name = &self.crate_name;
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+        let impls = self.views()?.into_iter().map(|(view, ty)| {
+            quote! {
+                impl #impl_generics #crate_name::VariantRef<#e #ty_generics> for #view #where_clause {
+                    type Variant = #ty;
+                }
+            }
+        });
+        Ok(quote! {
+            const _: () = {
+                #(#impls)*
+            };
+        })
+    }
+}
@@ -0,0 +1,266 @@
+//! Delegation of trait implementations to the variants of an enum.
+//!
+//! The `#[delegate]` attribute on a trait defines a hidden `macro_rules!` macro with the same name
+//! as the trait, which passes the trait definition on to `delegate_impl!`. The macro lives in the
+//! macro namespace, so it is imported together with the trait. The `#[newtype_enum]` macro calls
+//! this macro with the trait path and the enum, so `delegate_impl!` knows both the methods and the
+//! variants.
+
+use crate::{crate_name, ident_append, macro_reexport_vis, NewtypeEnum};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    visit::{self, Visit},
+    Error, FnArg, ItemEnum, ItemTrait, Pat, Path, ReturnType, Signature, Token, TraitItem, Type,
+};
+
+/// Define the hidden macro for a trait with the `#[delegate]` attribute.
+pub fn delegate_trait(attr: TokenStream, item: &ItemTrait) -> Result<TokenStream, Error> {
+    if !attr.is_empty() {
+        return Err(Error::new_spanned(attr, "unexpected arguments"));
+    }
+    if let Some(param) = item.generics.params.first() {
+        return Err(Error::new_spanned(
+            param,
+            "generic traits are not supported by `#[delegate]`",
+        ));
+    }
+
+    let mut signature = item.clone();
+    signature.attrs.clear();
+    for item in &mut signature.items {
+        match item {
+            TraitItem::Fn(item) => {
+                // Only the signature is needed, but the presence of a default body is kept.
+                item.attrs.clear();
+                if item.default.is_some() {
+                    item.default = Some(parse_quote!({}));
+                }
+            }
+            TraitItem::Type(item) => {
+                item.attrs.clear();
+                item.default = None;
+            }
+            TraitItem::Const(item) => {
+                item.attrs.clear();
+                item.default = None;
+            }
+            _ => {}
+        }
+    }
+
+    let crate_name = crate_name();
+    let ident = &item.ident;
+    let module = ident_append(
+        &Ident::new("__newtype_enum_delegate_", ident.span()),
+        &ident.unraw().to_string(),
+    );
+    let vis = macro_reexport_vis(&item.vis);
+    Ok(quote! {
+        #item
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #module {
+            macro_rules! #ident {
+                ($($input:tt)*) => {
+                    #crate_name::unstable::delegate_impl! { #signature $($input)* }
+                };
+            }
+
+            pub(crate) use #ident;
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #vis use #module::#ident;
+    })
+}
+
+/// The input of `delegate_impl!`: the trait definition, the trait path and the enum.
+pub struct DelegateInput {
+    item: ItemTrait,
+    path: Path,
+    e: ItemEnum,
+}
+
+impl Parse for DelegateInput {
+    fn parse(input: ParseStream) -> Result<Self, Error> {
+        let item = input.parse()?;
+        let path = input.parse()?;
+        input.parse::<Token![;]>()?;
+        let e = input.parse()?;
+        Ok(Self { item, path, e })
+    }
+}
+
+impl DelegateInput {
+    /// Implement the trait for the enum by forwarding every method to the current variant.
+    pub fn implement(&self) -> Result<TokenStream, Error> {
+        let Self { item, path, e } = self;
+        let ident = &e.ident;
+        let patterns = e
+            .variants
+            .iter()
+            .map(|var| {
+                let var_ident = &var.ident;
+                match var.attrs.iter().find(|attr| attr.path().is_ident("wrap")) {
+                    Some(attr) => {
+                        let wrapper: Path = attr.parse_args()?;
+                        Ok(quote!(#ident::#var_ident(#wrapper(v))))
+                    }
+                    None => Ok(quote!(#ident::#var_ident(v))),
+                }
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut generics = e.generics.clone();
+        for var in &e.variants {
+            let ty = &var.fields.iter().next().unwrap().ty;
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#ty: #path));
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        let mut methods = Vec::new();
+        for trait_item in &item.items {
+            match trait_item {
+                TraitItem::Fn(method) => {
+                    if method.sig.receiver().is_none() {
+                        if method.default.is_none() {
+                            return Err(Error::new_spanned(
+                                &method.sig,
+                                "cannot delegate a method without a `self` receiver",
+                            ));
+                        }
+                        continue;
+                    }
+                    check_signature(&method.sig)?;
+                    let mut sig = method.sig.clone();
+                    let mut args = Vec::new();
+                    for (index, input) in sig.inputs.iter_mut().enumerate() {
+                        if let FnArg::Typed(input) = input {
+                            let arg = Ident::new(&format!("__arg{index}"), Span::call_site());
+                            *input.pat = Pat::Verbatim(quote!(#arg));
+                            args.push(arg);
+                        }
+                    }
+                    let method = &sig.ident;
+                    let call = if sig.asyncness.is_some() {
+                        quote!(#path::#method(v, #(#args),*).await)
+                    } else {
+                        quote!(#path::#method(v, #(#args),*))
+                    };
+                    methods.push(quote! {
+                        #sig {
+                            match self {
+                                #(#patterns => #call,)*
+                            }
+                        }
+                    });
+                }
+                TraitItem::Type(item) => {
+                    return Err(Error::new_spanned(
+                        item,
+                        "cannot delegate a trait with associated types",
+                    ));
+                }
+                TraitItem::Const(item) => {
+                    return Err(Error::new_spanned(
+                        item,
+                        "cannot delegate a trait with associated constants",
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        let unsafety = &item.unsafety;
+        Ok(quote! {
+            #unsafety impl #impl_generics #path for #ident #ty_generics #where_clause {
+                #(#methods)*
+            }
+        })
+    }
+}
+
+/// Check that a method with a receiver can be forwarded to the variants.
+///
+/// The receiver cannot have a type (e.g. `self: Box<Self>`), because the enum is matched by value
+/// or by reference, and `Self` cannot be used in the arguments or the return type.
+fn check_signature(sig: &Signature) -> Result<(), Error> {
+    if let Some(receiver) = sig.receiver() {
+        if receiver.colon_token.is_some() {
+            return Err(Error::new_spanned(
+                receiver,
+                "cannot delegate a method with a typed `self` receiver, \
+                 use `self`, `&self` or `&mut self`",
+            ));
+        }
+    }
+    let types = sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(input) => Some(&*input.ty),
+        FnArg::Receiver(_) => None,
+    });
+    let output = match &sig.output {
+        ReturnType::Type(_, ty) => Some(&**ty),
+        ReturnType::Default => None,
+    };
+    if types.chain(output).any(uses_self) {
+        return Err(Error::new_spanned(
+            sig,
+            "cannot delegate a method with `Self` in its arguments or return type",
+        ));
+    }
+    Ok(())
+}
+
+/// Check if a type uses `Self`, which cannot be forwarded to the variants.
+fn uses_self(ty: &Type) -> bool {
+    struct UsesSelf(bool);
+
+    impl<'ast> Visit<'ast> for UsesSelf {
+        fn visit_path(&mut self, path: &'ast Path) {
+            self.0 |= path
+                .segments
+                .first()
+                .is_some_and(|segment| segment.ident == "Self");
+            visit::visit_path(self, path);
+        }
+    }
+
+    let mut visitor = UsesSelf(false);
+    visitor.visit_type(ty);
+    visitor.0
+}
+
+impl NewtypeEnum {
+    /// Call the hidden macros of the delegated traits.
+    pub(crate) fn implement_delegates(&self) -> TokenStream {
+        let mut e = self.define_enum();
+        e.attrs.clear();
+        let vars = self.item.variants.iter().zip(&self.options);
+        for (var, (orig, options)) in e.variants.iter_mut().zip(vars) {
+            var.attrs.clear();
+            var.discriminant = None;
+            // Wrapped variants delegate to the wrapped type instead of the generated struct.
+            if self.is_wrapped(orig, options) {
+                let path = self.variant_path(&self.struct_ident(orig, options));
+                var.attrs.push(parse_quote!(#[wrap(#path)]));
+                var.fields = orig.fields.clone();
+            }
+        }
+        let e = &e;
+        let delegates = self.delegates.iter().map(|path| {
+            quote! {
+                #path! { #path; #e }
+            }
+        });
+        quote!(#(#delegates)*)
+    }
+}
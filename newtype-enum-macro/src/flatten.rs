@@ -0,0 +1,241 @@
+//! Flattening of nested newtype enums.
+//!
+//! Every `#[newtype_enum]` defines a hidden `macro_rules!` macro `__newtype_enum_<Enum>`, which
+//! passes the indices and names of the `Variant` types of the enum on to `flatten_impl!`. A variant with the
+//! `#[newtype_enum(flatten)]` argument calls the macro of the inner enum, so `flatten_impl!` can
+//! implement `Variant` of the outer enum for every `<Inner as VariantAt<INDEX>>::Type`.
+//!
+//! The variant types are named by the projection, because their paths are only valid in the
+//! module of the inner enum. The coherence checker only normalizes the projection, if the
+//! `VariantAt` implementation is local, so the inner enum must be defined in the same crate.
+
+use crate::{ident_append, macro_reexport_vis, NewtypeEnum, UsedGenerics};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{
+    bracketed,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    visit::Visit,
+    Error, ItemEnum, LitInt, LitStr, Path, PathArguments, Token, Type,
+};
+
+/// Get the name of the hidden macro of an enum.
+fn macro_ident(ident: &Ident) -> Ident {
+    ident_append(
+        &Ident::new("__newtype_enum_", ident.span()),
+        &ident.unraw().to_string(),
+    )
+}
+
+/// The input of `flatten_impl!`: the variant indices and names of the inner enum, the crate name,
+/// the inner enum type, the variant of the outer enum with its index and the outer enum.
+pub struct FlattenInput {
+    indices: Vec<(LitInt, LitStr)>,
+    crate_name: Path,
+    ty: Type,
+    ident: Ident,
+    index: usize,
+    e: ItemEnum,
+}
+
+impl Parse for FlattenInput {
+    fn parse(input: ParseStream) -> Result<Self, Error> {
+        let content;
+        bracketed!(content in input);
+        let indices = Punctuated::<_, Token![,]>::parse_terminated_with(&content, |input| {
+            Ok((input.parse()?, input.parse()?))
+        })?
+        .into_iter()
+        .collect();
+        input.parse::<Token![;]>()?;
+        let crate_name = Path::parse_mod_style(input)?;
+        input.parse::<Token![;]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![;]>()?;
+        let ident = input.parse()?;
+        let index = input.parse::<LitInt>()?.base10_parse()?;
+        input.parse::<Token![;]>()?;
+        let e = input.parse()?;
+        Ok(Self {
+            indices,
+            crate_name,
+            ty,
+            ident,
+            index,
+            e,
+        })
+    }
+}
+
+impl FlattenInput {
+    /// Implement `Variant` of the outer enum for every variant type of the inner enum.
+    ///
+    /// The name of a variant type is qualified with the name of the flattened variant (e.g. `Number::Int`).
+    pub fn implement(&self) -> TokenStream {
+        let Self {
+            indices,
+            crate_name,
+            ty,
+            ident,
+            index,
+            e,
+        } = self;
+        let (impl_generics, ty_generics, where_clause) = e.generics.split_for_impl();
+        let v = quote!(#crate_name::unstable::VariantCore<#ty>);
+        let e_ident = &e.ident;
+        let e = quote!(#e_ident #ty_generics);
+
+        let impls = indices.iter().map(|(inner_index, inner_name)| {
+            let name = format!("{}::{}", ident.unraw(), inner_name.value());
+            let leaf = quote!(<#ty as #crate_name::unstable::VariantAt<#inner_index>>::Type);
+            quote! {
+                #[doc(hidden)]
+                impl #impl_generics #crate_name::unstable::VariantCore<#e> for #leaf #where_clause {
+                    fn into_enum(self) -> #e {
+                        #e_ident::#ident(<Self as #v>::into_enum(self))
+                    }
+
+                    fn from_enum(e: #e) -> ::core::option::Option<Self> {
+                        match e {
+                            #e_ident::#ident(e) => <Self as #v>::from_enum(e),
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+
+                    fn try_from_enum(e: #e) -> ::core::result::Result<Self, #e> {
+                        match e {
+                            #e_ident::#ident(e) => <Self as #v>::try_from_enum(e).map_err(#e_ident::#ident),
+                            e => ::core::result::Result::Err(e),
+                        }
+                    }
+
+                    fn ref_enum<'__e>(e: &'__e #e) -> ::core::option::Option<&'__e Self> {
+                        match e {
+                            #e_ident::#ident(e) => <Self as #v>::ref_enum(e),
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+
+                    fn mut_enum<'__e>(e: &'__e mut #e) -> ::core::option::Option<&'__e mut Self> {
+                        match e {
+                            #e_ident::#ident(e) => <Self as #v>::mut_enum(e),
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+
+                    fn is_enum_variant(e: &#e) -> bool {
+                        match e {
+                            #e_ident::#ident(e) => <Self as #v>::is_enum_variant(e),
+                            _ => false,
+                        }
+                    }
+                }
+                impl #impl_generics #crate_name::Variant<#e> for #leaf #where_clause {
+                    const NAME: &'static str = #name;
+                    const INDEX: usize = #index;
+                }
+            }
+        });
+        quote! {
+            const _: () = {
+                #(#impls)*
+            };
+        }
+    }
+}
+
+impl NewtypeEnum {
+    /// Define the hidden macro, which is called by enums that flatten this enum.
+    pub(crate) fn define_flatten_macro(&self) -> TokenStream {
+        let crate_name = &self.crate_name;
+        let ident = macro_ident(&self.item.ident);
+        let (indices, names): (Vec<_>, Vec<_>) = self
+            .item
+            .variants
+            .iter()
+            .enumerate()
+            .zip(&self.options)
+            .filter(|(_, options)| !options.skip)
+            .map(|((index, var), _)| (index, var.ident.unraw().to_string()))
+            .unzip();
+        let vis = macro_reexport_vis(&self.item.vis);
+        quote! {
+            #[doc(hidden)]
+            #[allow(unused_macros)]
+            macro_rules! #ident {
+                ($($input:tt)*) => {
+                    #crate_name::unstable::flatten_impl! { [#(#indices #names),*]; $($input)* }
+                };
+            }
+
+            #[doc(hidden)]
+            #[allow(unused_imports)]
+            #vis use #ident;
+        }
+    }
+
+    /// Check that the inner enums of flattened variants are named by a path without the generic parameters of the enum.
+    pub(crate) fn check_flatten(&self) -> Result<(), Error> {
+        let vars = self.item.variants.iter().zip(&self.options);
+        for (var, options) in vars.filter(|(_, options)| options.flatten) {
+            let ty = &var.fields.iter().next().unwrap().ty;
+            if options.wrap {
+                return Err(Error::new_spanned(
+                    &var.ident,
+                    "a flattened variant cannot be wrapped",
+                ));
+            }
+            if !matches!(ty, Type::Path(ty) if ty.qself.is_none()) {
+                return Err(Error::new_spanned(
+                    ty,
+                    "expected the path of a `#[newtype_enum]` enum",
+                ));
+            }
+            let mut used = UsedGenerics::new(&self.item.generics);
+            used.visit_type(ty);
+            if self
+                .item
+                .generics
+                .params
+                .iter()
+                .any(|param| used.contains(param))
+            {
+                return Err(Error::new_spanned(
+                    ty,
+                    "the type of a flattened variant cannot use the generic parameters of the enum",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Call the hidden macros of the inner enums of flattened variants.
+    pub(crate) fn implement_flatten(&self) -> TokenStream {
+        let crate_name = &self.crate_name;
+        let e = ItemEnum {
+            attrs: Vec::new(),
+            variants: Punctuated::new(),
+            ..self.item.clone()
+        };
+        let vars = self.item.variants.iter().enumerate().zip(&self.options);
+        let calls = vars
+            .filter(|(_, options)| options.flatten)
+            .map(|((index, var), _)| {
+                let ident = &var.ident;
+                let ty = &var.fields.iter().next().unwrap().ty;
+                let Type::Path(path) = ty else {
+                    unreachable!("checked by `check_flatten`")
+                };
+                let mut path = path.path.clone();
+                let last = path.segments.last_mut().unwrap();
+                last.ident = macro_ident(&last.ident);
+                last.arguments = PathArguments::None;
+                quote! {
+                    #path! { #crate_name; #ty; #ident #index; #e }
+                }
+            });
+        quote!(#(#calls)*)
+    }
+}
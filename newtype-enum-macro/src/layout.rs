@@ -0,0 +1,234 @@
+//! Names and placement of the generated structs.
+
+use crate::{ident_append, parse_vis_ident, super_vis, NewtypeEnum, VariantOptions};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use std::collections::HashMap;
+use syn::{
+    ext::IdentExt, meta::ParseNestedMeta, parse_quote, Error, ItemEnum, LitStr, Variant, Visibility,
+};
+
+/// The names and placement of the generated structs, set by the `variants`, `inline`, `reexport`,
+/// `struct_prefix` and `struct_suffix` arguments.
+pub struct Layout {
+    /// The name of the module with the generated structs.
+    module: Ident,
+    /// The visibility of the module with the generated structs.
+    module_vis: Visibility,
+    /// The `variants` argument, which cannot be combined with `inline`.
+    module_arg: Option<LitStr>,
+    /// The generated structs are defined next to the enum instead of in a module.
+    inline: bool,
+    /// The `reexport` argument: the generated structs are imported next to the enum.
+    reexport: Option<Ident>,
+    /// The prefix of the names of the generated structs.
+    prefix: String,
+    /// The suffix of the names of the generated structs.
+    suffix: String,
+}
+
+impl Layout {
+    pub fn new(item: &ItemEnum) -> Self {
+        Self {
+            module: ident_append(&item.ident, "_variants"),
+            module_vis: item.vis.clone(),
+            module_arg: None,
+            inline: false,
+            reexport: None,
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+
+    /// Parse a layout argument of the `#[newtype_enum]` attribute.
+    ///
+    /// Returns `false` if the argument is not a layout argument.
+    pub fn parse(&mut self, meta: &ParseNestedMeta) -> Result<bool, Error> {
+        if meta.path.is_ident("variants") {
+            let lit: LitStr = meta.value()?.parse()?;
+            let (vis, ident) = parse_vis_ident(&lit)?;
+            if let Some(vis) = vis {
+                self.module_vis = vis;
+            }
+            self.module = ident;
+            self.module_arg = Some(lit);
+        } else if meta.path.is_ident("inline") {
+            self.inline = true;
+        } else if meta.path.is_ident("reexport") {
+            self.reexport = meta.path.get_ident().cloned();
+        } else if meta.path.is_ident("struct_prefix") || meta.path.is_ident("struct_suffix") {
+            let lit: LitStr = meta.value()?.parse()?;
+            let (affix, ident) = if meta.path.is_ident("struct_prefix") {
+                (&mut self.prefix, format!("{}_", lit.value()))
+            } else {
+                (&mut self.suffix, format!("_{}", lit.value()))
+            };
+            if syn::parse_str::<Ident>(&ident).is_err() {
+                return Err(Error::new_spanned(lit, "expected a part of an identifier"));
+            }
+            *affix = lit.value();
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that the `variants` and `reexport` arguments are not combined with `inline`.
+    pub fn check(&self) -> Result<(), Error> {
+        if !self.inline {
+            return Ok(());
+        }
+        if let Some(lit) = &self.module_arg {
+            return Err(Error::new_spanned(
+                lit,
+                "the `variants` argument cannot be combined with `inline`",
+            ));
+        }
+        if let Some(ident) = &self.reexport {
+            return Err(Error::new_spanned(
+                ident,
+                "the `reexport` argument cannot be combined with `inline`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check if the names of the generated structs are visible next to the enum.
+    pub const fn in_enum_scope(&self) -> bool {
+        self.inline || self.reexport.is_some()
+    }
+
+    /// Describe the location of the generated structs (used in error messages).
+    pub fn location(&self) -> String {
+        if self.inline {
+            "next to the enum".to_string()
+        } else {
+            format!("in the `{}` module", self.module)
+        }
+    }
+}
+
+impl NewtypeEnum {
+    /// Get the name of the generated struct of a variant.
+    ///
+    /// The `rename` argument of the variant takes precedence over the prefix and suffix of the enum.
+    pub(crate) fn struct_ident(&self, var: &Variant, options: &VariantOptions) -> Ident {
+        if let Some(rename) = &options.rename {
+            return rename.clone();
+        }
+        let Layout { prefix, suffix, .. } = &self.layout;
+        if prefix.is_empty() && suffix.is_empty() {
+            return var.ident.clone();
+        }
+        Ident::new(
+            &format!("{prefix}{}{suffix}", var.ident.unraw()),
+            var.ident.span(),
+        )
+    }
+
+    /// Check that the `rename` argument is only used for generated structs and that the
+    /// names of all generated structs are unique.
+    ///
+    /// With the `inline` or `reexport` argument the names must also differ from the name of the enum.
+    pub(crate) fn check_struct_idents(&self) -> Result<(), Error> {
+        let mut idents = HashMap::new();
+        for (var, options) in self.item.variants.iter().zip(&self.options) {
+            if self.is_newtype(var, options) {
+                if let Some(rename) = &options.rename {
+                    return Err(Error::new_spanned(
+                        rename,
+                        "the `rename` argument requires a generated struct, \
+                         use `#[newtype_enum(wrap)]` to wrap the newtype variant",
+                    ));
+                }
+                continue;
+            }
+            let ident = self.struct_ident(var, options);
+            if (self.layout.inline || self.layout.reexport.is_some())
+                && ident.unraw() == self.item.ident.unraw()
+            {
+                return Err(Error::new_spanned(
+                    options.rename.as_ref().unwrap_or(&var.ident),
+                    format!("the struct name `{ident}` is already used by the enum"),
+                ));
+            }
+            if let Some(other) = idents.insert(ident.to_string(), &var.ident) {
+                return Err(Error::new_spanned(
+                    options.rename.as_ref().unwrap_or(&var.ident),
+                    format!("the struct name `{ident}` is already used by the variant `{other}`"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the path of the generated struct of a variant.
+    pub(crate) fn variant_path(&self, ident: &Ident) -> TokenStream {
+        if self.layout.inline {
+            quote!(#ident)
+        } else {
+            let module = &self.layout.module;
+            quote!(#module::#ident)
+        }
+    }
+
+    /// Get the link to the documentation of the generated struct of a variant.
+    pub(crate) fn variant_doc_link(&self, ident: &Ident) -> String {
+        if self.layout.inline {
+            format!("struct.{ident}.html")
+        } else {
+            format!("{}/struct.{ident}.html", self.layout.module)
+        }
+    }
+
+    /// Get the visibility of the generated structs.
+    ///
+    /// Inside the module, the visibility of the enum is lifted by one level (see `super_vis`).
+    pub(crate) fn struct_vis(&self) -> Result<Visibility, Error> {
+        if self.layout.inline {
+            Ok(self.item.vis.clone())
+        } else {
+            super_vis(&self.item.vis, || parse_quote!(pub(super)))
+        }
+    }
+
+    /// Get the visibility of a field of a generated struct.
+    ///
+    /// Fields without a visibility get the visibility of the struct.
+    pub(crate) fn field_vis(
+        &self,
+        vis: &Visibility,
+        struct_vis: &Visibility,
+    ) -> Result<Visibility, Error> {
+        match vis {
+            Visibility::Inherited => Ok(struct_vis.clone()),
+            vis if self.layout.inline => Ok(vis.clone()),
+            vis => super_vis(vis, || struct_vis.clone()),
+        }
+    }
+
+    /// Place the generated structs in the module or next to the enum.
+    ///
+    /// With the `reexport` argument, the structs are imported next to the enum with the visibility of the enum.
+    pub(crate) fn variants_module(&self, idents: &[Ident], items: TokenStream) -> TokenStream {
+        if self.layout.inline {
+            return items;
+        }
+        let module = &self.layout.module;
+        let module_vis = &self.layout.module_vis;
+        let doc = format!("The generated variants of the `{}` enum.", self.item.ident);
+        let reexport = self.layout.reexport.is_some().then(|| {
+            let vis = &self.item.vis;
+            quote!(#vis use #module::{#(#idents),*};)
+        });
+        quote! {
+            #[allow(non_snake_case)]
+            #[doc = #doc]
+            #module_vis mod #module {
+                use super::*;
+                #items
+            }
+            #reexport
+        }
+    }
+}
@@ -4,11 +4,29 @@
 
 extern crate proc_macro;
 
+mod attrs;
+mod borrow;
+mod delegate;
+mod flatten;
+mod layout;
+mod self_type;
+mod serde;
+mod shadow;
+
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use quote::{quote, ToTokens};
+use std::collections::{HashMap, HashSet};
 use syn::{
-    meta, parse::Parser, parse_macro_input, parse_quote, token::Struct, Error, Fields, Generics,
-    ItemEnum, ItemStruct, LitStr, Meta, Path, Variant, VisRestricted, Visibility,
+    ext::IdentExt,
+    meta,
+    parse::{ParseStream, Parser},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    token::Struct,
+    visit::{self, Visit},
+    Attribute, Error, Fields, GenericArgument, GenericParam, Generics, ItemEnum, ItemStruct,
+    Lifetime, LitStr, Meta, Path, PathArguments, Token, Type, Variant, VisRestricted, Visibility,
+    WhereClause,
 };
 
 /// Derive the `Enum` and `Variant` traits from the `newtype-enum` crate.
@@ -20,6 +38,36 @@ pub fn newtype_enum(
     newtype_enum_impl(attr.into(), parse_macro_input!(item)).into()
 }
 
+/// Allow delegating the implementation of a trait to the variants of a newtype enum.
+#[proc_macro_attribute]
+pub fn delegate(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    delegate::delegate_trait(attr.into(), &parse_macro_input!(item))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implement a delegated trait for a newtype enum (used by the `#[delegate]` attribute).
+#[doc(hidden)]
+#[proc_macro]
+pub fn delegate_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    parse_macro_input!(input as delegate::DelegateInput)
+        .implement()
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implement the variants of a flattened enum for an outer newtype enum (used by `#[newtype_enum(flatten)]`).
+#[doc(hidden)]
+#[proc_macro]
+pub fn flatten_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    parse_macro_input!(input as flatten::FlattenInput)
+        .implement()
+        .into()
+}
+
 macro_rules! unwrap_or_compile_error {
     ($expr:expr) => {
         match $expr {
@@ -35,26 +83,80 @@ fn newtype_enum_impl(meta: TokenStream, item: ItemEnum) -> TokenStream {
     let enum_item = e.define_enum();
     let mod_variants = e.define_variants();
     let impl_variants = e.implement_variants();
+    let impl_discriminant = e.implement_discriminant();
+    let kind = e.implement_kind();
+    let ref_enum = e.implement_view(e.ref_enum.as_ref(), false);
+    let mut_enum = e.implement_view(e.mut_enum.as_ref(), true);
+    let visitor = e.implement_visitor();
+    let match_with = e.implement_match_with();
+    let default = e.implement_default();
+    let conversions = e.implement_conversions();
+    let subsets = e.implement_subsets();
+    let views = unwrap_or_compile_error!(e.implement_views());
+    let delegates = e.implement_delegates();
+    let flatten_macro = e.define_flatten_macro();
+    let flatten = e.implement_flatten();
+    let serde = e.implement_serde();
     quote! {
         #enum_item
         #mod_variants
         #impl_variants
+        #impl_discriminant
+        #kind
+        #ref_enum
+        #mut_enum
+        #visitor
+        #match_with
+        #default
+        #conversions
+        #subsets
+        #views
+        #delegates
+        #flatten_macro
+        #flatten
+        #serde
     }
 }
 
+#[allow(clippy::struct_excessive_bools)]
 struct NewtypeEnum {
     item: ItemEnum,
     crate_name: Path,
-    variants: Ident,
-    variants_vis: Visibility,
+    layout: layout::Layout,
+    repr: Option<Ident>,
+    discriminant: Option<Ident>,
+    kind: Option<(Visibility, Ident)>,
+    ref_enum: Option<(Visibility, Ident)>,
+    mut_enum: Option<(Visibility, Ident)>,
+    visitor: Option<(Visibility, Ident)>,
+    from: bool,
+    try_from: bool,
+    match_with: bool,
+    subset_of: Vec<Type>,
+    delegates: Vec<Path>,
+    serde: bool,
+    serde_attrs: Vec<Meta>,
+    routing: attrs::Routing,
+    options: Vec<VariantOptions>,
 }
 
 impl NewtypeEnum {
-    fn new(meta: TokenStream, item: ItemEnum) -> Result<Self, Error> {
+    fn new(meta: TokenStream, mut item: ItemEnum) -> Result<Self, Error> {
         let mut crate_name = crate_name();
 
-        let mut variants = ident_append(&item.ident, "_variants");
-        let mut variants_vis = item.vis.clone();
+        let mut layout = layout::Layout::new(&item);
+        let mut discriminant = None;
+        let mut kind = None;
+        let mut ref_enum = None;
+        let mut mut_enum = None;
+        let mut visitor = None;
+        let mut from = false;
+        let mut try_from = false;
+        let mut match_with = false;
+        let mut subset_of = Vec::new();
+        let mut delegates = Vec::new();
+        let mut serde = false;
+        let mut routing = attrs::Routing::default();
 
         if !meta.is_empty() {
             meta::parser(|meta| {
@@ -62,20 +164,47 @@ impl NewtypeEnum {
                     .path
                     .get_ident()
                     .ok_or_else(|| Error::new_spanned(&meta.path, "expected identifier"))?;
-                if ident == "variants" {
+                // Generated items with a name and an optional visibility.
+                let named_item = match ident.to_string().as_str() {
+                    "kind" => Some(&mut kind),
+                    "ref_enum" => Some(&mut ref_enum),
+                    "mut_enum" => Some(&mut mut_enum),
+                    "visitor" => Some(&mut visitor),
+                    _ => None,
+                };
+                // Flags to generate additional methods and implementations.
+                let flag = match ident.to_string().as_str() {
+                    "from" => Some(&mut from),
+                    "try_from" => Some(&mut try_from),
+                    "match_with" => Some(&mut match_with),
+                    _ => None,
+                };
+                if let Some(named_item) = named_item {
+                    let (vis, ident) = parse_vis_ident(&meta.value()?.parse()?)?;
+                    *named_item = Some((vis.unwrap_or_else(|| item.vis.clone()), ident));
+                } else if let Some(flag) = flag {
+                    *flag = true;
+                } else if ident == "discriminant" {
+                    discriminant = Some(ident.clone());
+                } else if ident == "subset_of" {
                     let lit: LitStr = meta.value()?.parse()?;
-                    let s = lit.value();
-                    let s = if let Some(index) = s.rfind(' ') {
-                        variants_vis = syn::parse_str(&s[..index])
-                            .map_err(|err| Error::new_spanned(&lit, err))?;
-                        &s[index + 1..]
-                    } else {
-                        &s
-                    };
-                    variants = syn::parse_str(s).map_err(|err| Error::new_spanned(&lit, err))?;
+                    subset_of.push(lit.parse()?);
+                } else if ident == "delegate" {
+                    meta.parse_nested_meta(|meta| {
+                        delegates.push(meta.path);
+                        Ok(())
+                    })?;
+                } else if ident == "serde" {
+                    if !cfg!(feature = "serde") {
+                        return Err(Error::new_spanned(
+                            ident,
+                            "the `serde` argument requires the `serde` feature of the `newtype-enum` crate",
+                        ));
+                    }
+                    serde = true;
                 } else if ident == "unstable_self_test" {
                     crate_name = parse_quote!(self);
-                } else {
+                } else if !routing.parse(&meta)? && !layout.parse(&meta)? {
                     return Err(Error::new_spanned(ident, "unknown argument"));
                 }
                 Ok(())
@@ -83,29 +212,289 @@ impl NewtypeEnum {
             .parse2(meta)?;
         }
 
-        Ok(Self {
+        let repr = primitive_repr(&routing.enum_attrs(&item.attrs))?;
+        self_type::resolve(&mut item)?;
+
+        let serde_attrs = serde::take_attrs(&mut item.attrs)?;
+        let options = item
+            .variants
+            .iter_mut()
+            .map(VariantOptions::new)
+            .collect::<Result<_, _>>()?;
+
+        let e = Self {
             item,
             crate_name,
-            variants,
-            variants_vis,
-        })
+            layout,
+            repr,
+            discriminant,
+            kind,
+            ref_enum,
+            mut_enum,
+            visitor,
+            from,
+            try_from,
+            match_with,
+            subset_of,
+            delegates,
+            serde,
+            serde_attrs,
+            routing,
+            options,
+        };
+        e.check()?;
+        Ok(e)
     }
 
-    fn variants(&self) -> impl Iterator<Item = Variant> + '_ {
-        self.item.variants.iter().map(move |var| match &var.fields {
+    /// Check the enum and the arguments for unsupported combinations.
+    fn check(&self) -> Result<(), Error> {
+        self.layout.check()?;
+        self.check_struct_idents()?;
+        self.check_discriminants()?;
+        self.check_kind_vis()?;
+        self.check_unique_types()?;
+        self.check_snake_case_names()?;
+        self.check_flatten()?;
+        self.check_default()?;
+        self.check_serde_tag()?;
+        if self.serde {
+            self.check_serde_fields()?;
+        }
+        Ok(())
+    }
+
+    /// Check that explicit discriminants and the `discriminant` argument are only used with a primitive representation.
+    ///
+    /// All variants of the transformed enum have fields, which requires a primitive representation.
+    fn check_discriminants(&self) -> Result<(), Error> {
+        if self.repr.is_none() {
+            if let Some((_, discriminant)) = self
+                .item
+                .variants
+                .iter()
+                .find_map(|var| var.discriminant.as_ref())
+            {
+                return Err(Error::new_spanned(
+                    discriminant,
+                    "explicit discriminants require a primitive representation, e.g. `#[repr(u8)]`",
+                ));
+            }
+            if let Some(ident) = &self.discriminant {
+                return Err(Error::new_spanned(
+                    ident,
+                    "the `discriminant` argument requires a primitive representation, e.g. `#[repr(u8)]`",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the kind enum is at least as visible as the enum.
+    ///
+    /// The kind enum is used as the associated `Kind` type of the `EnumKind` implementation,
+    /// which must not leak a private type through the interface of the enum.
+    fn check_kind_vis(&self) -> Result<(), Error> {
+        if let Some((vis, _)) = &self.kind {
+            if vis_rank(vis) < vis_rank(&self.item.vis) {
+                return Err(Error::new_spanned(
+                    vis,
+                    "the visibility of the kind enum must not be narrower than the visibility of the enum",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the wrapped types of all newtype variants are unique.
+    ///
+    /// Otherwise the `Variant` implementations would conflict with each other.
+    fn check_unique_types(&self) -> Result<(), Error> {
+        let mut types = HashMap::new();
+        let vars = self.item.variants.iter().zip(&self.options);
+        for (var, _) in vars.filter(|(var, options)| !options.skip && self.is_newtype(var, options))
+        {
+            let ty = &var.fields.iter().next().unwrap().ty;
+            if let Some(other) = types.insert(quote!(#ty).to_string(), &var.ident) {
+                return Err(Error::new_spanned(
+                    ty,
+                    format!(
+                        "the wrapped type `{}` is already used by the variant `{other}`\n\
+                         the wrapped type must uniquely identify the variant, \
+                         use `#[newtype_enum(wrap)]` to generate a distinct type \
+                         {} or skip the `Variant` implementation \
+                         with `#[newtype_enum(skip)]`",
+                        quote!(#ty),
+                        self.layout.location(),
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the snake case names of all variants are unique.
+    ///
+    /// The visitor methods and the `match_with` closures are named after the variants, so
+    /// `HTTPServer` and `HttpServer` would both generate a `visit_http_server` method.
+    fn check_snake_case_names(&self) -> Result<(), Error> {
+        if self.visitor.is_none() && !self.match_with {
+            return Ok(());
+        }
+        let mut names = HashMap::new();
+        for var in &self.item.variants {
+            let name = snake_case(&var.ident.unraw().to_string());
+            if let Some(other) = names.insert(name.clone(), &var.ident) {
+                return Err(Error::new_spanned(
+                    &var.ident,
+                    format!(
+                        "the snake case name `{name}` of the variant is already used by the variant `{other}`"
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if the variant is a newtype variant that is kept as it is.
+    fn is_newtype(&self, var: &Variant, options: &VariantOptions) -> bool {
+        matches!(&var.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+            && !self.is_wrapped(var, options)
+    }
+
+    /// Check if the newtype variant is converted to a generated tuple struct.
+    ///
+    /// Newtype variants that wrap a bare type parameter of the enum are always wrapped,
+    /// because a `Variant` implementation for a type parameter would conflict with the
+    /// implementations for all other variants.
+    fn is_wrapped(&self, var: &Variant, options: &VariantOptions) -> bool {
+        match &var.fields {
             Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
-                let mut var = var.clone();
-                var.discriminant = None;
-                var
+                options.wrap || self.is_uncovered_type_param(&fields.unnamed[0].ty)
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if the type is a type parameter of the enum, which is not covered by a local type.
+    ///
+    /// References, `Box` and `Pin` are fundamental types and do not cover their type parameter.
+    fn is_uncovered_type_param(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Path(ty) if ty.qself.is_none() => {
+                if let Some(ident) = ty.path.get_ident() {
+                    return self
+                        .item
+                        .generics
+                        .type_params()
+                        .any(|param| param.ident == *ident);
+                }
+                let segment = ty.path.segments.last().unwrap();
+                match &segment.arguments {
+                    PathArguments::AngleBracketed(args)
+                        if (segment.ident == "Box" || segment.ident == "Pin")
+                            && args.args.len() == 1 =>
+                    {
+                        matches!(&args.args[0], GenericArgument::Type(ty) if self.is_uncovered_type_param(ty))
+                    }
+                    _ => false,
+                }
             }
-            _ => {
+            Type::Reference(ty) => self.is_uncovered_type_param(&ty.elem),
+            Type::Paren(ty) => self.is_uncovered_type_param(&ty.elem),
+            Type::Group(ty) => self.is_uncovered_type_param(&ty.elem),
+            _ => false,
+        }
+    }
+
+    /// Get the subset of the enum generics that are used by the fields of a variant.
+    ///
+    /// Bounds, defaults and where predicates that reference unused parameters are removed.
+    fn variant_generics(&self, fields: &Fields) -> Generics {
+        let generics = &self.item.generics;
+        let mut used = UsedGenerics::new(generics);
+        used.visit_fields(fields);
+
+        let params = generics
+            .params
+            .iter()
+            .filter(|param| used.contains(param))
+            .cloned()
+            .map(|mut param| {
+                match &mut param {
+                    GenericParam::Type(param) => {
+                        param.bounds = param
+                            .bounds
+                            .iter()
+                            .filter(|bound| used.covers(|v| v.visit_type_param_bound(bound)))
+                            .cloned()
+                            .collect();
+                        if let Some(default) = &param.default {
+                            if !used.covers(|v| v.visit_type(default)) {
+                                param.eq_token = None;
+                                param.default = None;
+                            }
+                        }
+                    }
+                    GenericParam::Lifetime(param) => {
+                        param.bounds = param
+                            .bounds
+                            .iter()
+                            .filter(|bound| used.covers(|v| v.visit_lifetime(bound)))
+                            .cloned()
+                            .collect();
+                    }
+                    GenericParam::Const(param) => {
+                        if let Some(default) = &param.default {
+                            if !used.covers(|v| v.visit_expr(default)) {
+                                param.eq_token = None;
+                                param.default = None;
+                            }
+                        }
+                    }
+                }
+                param
+            })
+            .collect();
+
+        let predicates: Punctuated<_, _> = generics
+            .where_clause
+            .iter()
+            .flat_map(|where_clause| &where_clause.predicates)
+            .filter(|predicate| used.covers(|v| v.visit_where_predicate(predicate)))
+            .cloned()
+            .collect();
+
+        Generics {
+            params,
+            where_clause: (!predicates.is_empty()).then(|| WhereClause {
+                where_token: Token![where](Span::call_site()),
+                predicates,
+            }),
+            ..generics.clone()
+        }
+    }
+
+    fn variants(&self) -> impl Iterator<Item = Variant> + '_ {
+        let vars = self.item.variants.iter().zip(&self.options);
+        vars.map(move |(var, options)| {
+            if self.is_newtype(var, options) {
+                var.clone()
+            } else {
                 let ident = &var.ident;
-                let variants = &self.variants;
-                let doc = format!("See [`{ident}`]({variants}/struct.{ident}.html).");
-                parse_quote! {
+                let struct_ident = self.struct_ident(var, options);
+                let path = self.variant_path(&struct_ident);
+                let generics = self.variant_generics(&var.fields);
+                let (_, ty_generics, _) = generics.split_for_impl();
+                let doc = format!(
+                    "See [`{struct_ident}`]({}).",
+                    self.variant_doc_link(&struct_ident)
+                );
+                let mut new_var: Variant = parse_quote! {
                     #[doc = #doc]
-                    #ident(#variants::#ident)
-                }
+                    #ident(#path #ty_generics)
+                };
+                new_var.discriminant.clone_from(&var.discriminant);
+                new_var
             }
         })
     }
@@ -113,73 +502,100 @@ impl NewtypeEnum {
     fn define_enum(&self) -> ItemEnum {
         let mut item = self.item.clone();
         item.variants = self.variants().collect();
+        item.attrs = attrs::without_derive_default(self.enum_attrs());
+        if !self.serde {
+            item.attrs.extend(serde::enum_attr(&self.serde_attrs));
+            let vars = self.item.variants.iter().zip(&self.options);
+            for (new_var, (var, options)) in item.variants.iter_mut().zip(vars) {
+                let generated = !self.is_newtype(var, options);
+                new_var
+                    .attrs
+                    .extend(serde::variant_attr(&options.serde_attrs, generated));
+            }
+        }
         item
     }
 
     fn define_variants(&self) -> TokenStream {
-        let items = self.item.variants.iter().filter(
-            |var| !matches!(&var.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1),
-        );
+        let vars = self.item.variants.iter().zip(&self.options);
+        let items = vars.filter(|(var, options)| !self.is_newtype(var, options));
 
         if items.clone().next().is_none() {
             return TokenStream::new();
         }
 
-        let vis = unwrap_or_compile_error!(super_vis(&self.item.vis, || parse_quote!(pub(super))));
-        let item_attrs = self.item.attrs.iter().filter(
-            |attr| !matches!(&attr.meta, Meta::NameValue(meta) if meta.path.is_ident("doc")),
-        );
-
-        let items = items.map(move |var| {
-            let mut item = ItemStruct {
-                attrs: item_attrs
-                    .clone()
-                    .chain(var.attrs.iter())
-                    .cloned()
-                    .collect(),
-                vis: vis.clone(),
-                struct_token: Struct::default(),
-                ident: var.ident.clone(),
-                generics: Generics::default(),
-                fields: var.fields.clone(),
-                semi_token: None,
-            };
-            match &mut item.fields {
-                Fields::Unit => {
-                    item.semi_token = parse_quote!(;);
-                }
-                Fields::Named(fields) => {
-                    for field in &mut fields.named {
-                        field.vis = unwrap_or_compile_error!(super_vis(&field.vis, || vis.clone()));
+        let vis = unwrap_or_compile_error!(self.struct_vis());
+        let serde_derive = self.serde_derive();
+        let serde_attr = serde::enum_struct_attr(&self.serde_attrs);
+
+        let mut aliases = TokenStream::new();
+        let mut idents = Vec::new();
+        let items: Vec<_> = items
+            .map(|(var, options)| {
+                let default = options.default.then(|| self.derive_default()).flatten();
+                let mut attrs = self
+                    .routing
+                    .struct_attrs(&self.item.attrs, &var.attrs, default);
+                attrs.extend(serde_derive.iter().cloned());
+                attrs.extend(serde_attr.clone());
+                attrs.extend(serde::variant_struct_attr(&options.serde_attrs));
+                let mut item = ItemStruct {
+                    attrs,
+                    vis: vis.clone(),
+                    struct_token: Struct::default(),
+                    ident: self.struct_ident(var, options),
+                    generics: self.variant_generics(&var.fields),
+                    fields: var.fields.clone(),
+                    semi_token: None,
+                };
+                aliases.extend(self.alias_shadowed_fields(&var.ident, &mut item.fields));
+                idents.push(item.ident.clone());
+                match &mut item.fields {
+                    Fields::Unit => {
+                        item.semi_token = parse_quote!(;);
+                    }
+                    Fields::Named(fields) => {
+                        for field in &mut fields.named {
+                            field.vis = unwrap_or_compile_error!(self.field_vis(&field.vis, &vis));
+                        }
+                    }
+                    Fields::Unnamed(fields) => {
+                        item.semi_token = parse_quote!(;);
+                        for field in &mut fields.unnamed {
+                            field.vis = unwrap_or_compile_error!(self.field_vis(&field.vis, &vis));
+                        }
                     }
                 }
-                Fields::Unnamed(_) => {
-                    return Error::new_spanned(var, "unsupported variant type").to_compile_error();
+                if self.is_wrapped(var, options) {
+                    let wrapper_impls = wrapper_impls(&item);
+                    quote! {
+                        #item
+                        #wrapper_impls
+                    }
+                } else {
+                    quote!(#item)
                 }
-            };
-            quote!(#item)
-        });
+            })
+            .collect();
 
-        let variants = &self.variants;
-        let variants_vis = &self.variants_vis;
-        let doc = format!("The generated variants of the `{}` enum.", self.item.ident);
+        let module = self.variants_module(&idents, quote!(#(#items)*));
         quote! {
-            #[allow(non_snake_case)]
-            #[doc = #doc]
-            #variants_vis mod #variants {
-                use super::*;
-                #(#items)*
-            }
+            #aliases
+            #module
         }
     }
 
     fn implement_variants(&self) -> TokenStream {
         let e = &self.item.ident;
         let crate_name = &self.crate_name;
-        let impls = self.variants().map(|var| {
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+        let vars = self.variants().enumerate().zip(&self.options);
+        let impls = vars.filter(|(_, options)| !options.skip).map(|((index, var), _)| {
             let ident = &var.ident;
+            let name = ident.unraw().to_string();
             let ty = &var.fields.iter().next().unwrap().ty;
             let v = quote!(#e::#ident);
+            let e = quote!(#e #ty_generics);
             let match_from = quote!{
                 match e {
                     #v(v) => Some(v),
@@ -188,7 +604,7 @@ impl NewtypeEnum {
             };
             quote!(
                 #[doc(hidden)]
-                impl #crate_name::unstable::VariantCore<#e> for #ty {
+                impl #impl_generics #crate_name::unstable::VariantCore<#e> for #ty #where_clause {
                     fn into_enum(self) -> #e {
                         #v(self)
                     }
@@ -197,11 +613,18 @@ impl NewtypeEnum {
                         #match_from
                     }
 
-                    fn ref_enum(e: &#e) -> ::core::option::Option<&Self>{
+                    fn try_from_enum(e: #e) -> ::core::result::Result<Self, #e> {
+                        match e {
+                            #v(v) => ::core::result::Result::Ok(v),
+                            e => ::core::result::Result::Err(e),
+                        }
+                    }
+
+                    fn ref_enum<'__e>(e: &'__e #e) -> ::core::option::Option<&'__e Self> {
                         #match_from
                     }
 
-                    fn mut_enum(e: &mut #e) -> ::core::option::Option<&mut Self> {
+                    fn mut_enum<'__e>(e: &'__e mut #e) -> ::core::option::Option<&'__e mut Self> {
                         #match_from
                     }
 
@@ -212,7 +635,10 @@ impl NewtypeEnum {
                     fn from_enum_unwrap(e: #e) -> Self {
                         match e {
                             #v(v) => v,
-                            _ => ::core::panic!("called `Variant::from_enum_unwrap` on another enum variant"),
+                            e => ::core::panic!(
+                                "called `Enum::into_variant_unwrap` on another enum variant: {}",
+                                #crate_name::VariantMismatch::new::<Self>(e),
+                            ),
                         }
                     }
 
@@ -223,18 +649,539 @@ impl NewtypeEnum {
                         }
                     }
                 }
-                impl #crate_name::Variant<#e> for #ty { }
+                impl #impl_generics #crate_name::Variant<#e> for #ty #where_clause {
+                    const NAME: &'static str = #name;
+                    const INDEX: usize = #index;
+                }
+                impl #impl_generics #crate_name::unstable::VariantAt<#index> for #e #where_clause {
+                    type Type = #ty;
+                }
             )
         });
+        let idents = self.item.variants.iter().map(|var| &var.ident);
+        let names = idents.clone().map(|ident| ident.unraw().to_string());
+        let indices = 0..self.item.variants.len();
+        let variant_list = self
+            .variants()
+            .zip(&self.options)
+            .filter(|(_, options)| !options.skip)
+            .map(|(var, _)| var.fields.into_iter().next().unwrap().ty)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .fold(
+                quote!(#crate_name::hlist::HNil),
+                |tail, ty| quote!(#crate_name::hlist::HCons<#ty, #tail>),
+            );
+        let no_skipped = self.options.iter().all(|options| !options.skip).then(|| {
+            quote! {
+                impl #impl_generics #crate_name::matcher::NoSkippedVariants for #e #ty_generics #where_clause {}
+            }
+        });
+        quote! {
+            const _: () = {
+                #no_skipped
+                impl #impl_generics #crate_name::Enum for #e #ty_generics #where_clause {
+                    const VARIANT_NAMES: &'static [&'static str] = &[#(#names),*];
+
+                    type Variants = #variant_list;
+
+                    fn variant_index(&self) -> usize {
+                        match *self {
+                            #(#e::#idents(_) => #indices,)*
+                        }
+                    }
+                }
+                #(#impls)*
+            };
+        }
+    }
+
+    fn implement_discriminant(&self) -> TokenStream {
+        let (Some(_), Some(repr)) = (&self.discriminant, &self.repr) else {
+            return TokenStream::new();
+        };
+        let e = &self.item.ident;
+        let vis = &self.item.vis;
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+        quote! {
+            impl #impl_generics #e #ty_generics #where_clause {
+                /// Get the discriminant of the current enum variant.
+                #[allow(dead_code)]
+                #vis fn discriminant(&self) -> #repr {
+                    // SAFETY: The enum has a primitive representation, so it is a `repr(C)` union
+                    // of `repr(C)` structs, which all start with the discriminant.
+                    unsafe { *(self as *const Self).cast::<#repr>() }
+                }
+            }
+        }
+    }
+
+    fn implement_kind(&self) -> TokenStream {
+        let Some((vis, kind)) = &self.kind else {
+            return TokenStream::new();
+        };
+        let e = &self.item.ident;
+        let crate_name = &self.crate_name;
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+
+        let idents = self.item.variants.iter().map(|var| &var.ident);
+        let kind_variants = idents.clone().map(|ident| {
+            let doc = format!("The kind of the `{}` variant.", ident.unraw());
+            quote! {
+                #[doc = #doc]
+                #ident
+            }
+        });
+
+        let vars = self.variants().zip(&self.options);
+        let impls = vars.filter(|(_, options)| !options.skip).map(|(var, _)| {
+            let ident = &var.ident;
+            let ty = &var.fields.iter().next().unwrap().ty;
+            quote! {
+                impl #impl_generics #crate_name::VariantKind<#e #ty_generics> for #ty #where_clause {
+                    const KIND: #kind = #kind::#ident;
+                }
+            }
+        });
+
+        let doc = format!("The variant kinds of the `{e}` enum.");
+        let method_doc = format!("Get the kind of the current variant as [`{kind}`].");
+        quote! {
+            #[doc = #doc]
+            #[derive(
+                ::core::clone::Clone,
+                ::core::marker::Copy,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::cmp::PartialOrd,
+                ::core::cmp::Ord,
+                ::core::hash::Hash,
+                ::core::fmt::Debug,
+            )]
+            #vis enum #kind {
+                #(#kind_variants,)*
+            }
+
+            impl #impl_generics #e #ty_generics #where_clause {
+                #[doc = #method_doc]
+                #[allow(dead_code)]
+                #vis fn kind(&self) -> #kind {
+                    match *self {
+                        #(#e::#idents(_) => #kind::#idents,)*
+                    }
+                }
+            }
+
+            const _: () = {
+                impl #impl_generics #crate_name::EnumKind for #e #ty_generics #where_clause {
+                    type Kind = #kind;
+
+                    fn kind(&self) -> #kind {
+                        Self::kind(self)
+                    }
+                }
+                #(#impls)*
+            };
+        }
+    }
+
+    /// Generate an enum which holds a (mutable) reference to the current variant.
+    fn implement_view(&self, view: Option<&(Visibility, Ident)>, mutable: bool) -> TokenStream {
+        let Some((vis, view)) = view else {
+            return TokenStream::new();
+        };
+        if self.item.variants.is_empty() {
+            return Error::new_spanned(view, "expected an enum with at least one variant")
+                .to_compile_error();
+        }
+        let e = &self.item.ident;
+        let lifetime = self.fresh_lifetime();
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+
+        let mut params = self.item.generics.params.clone();
+        params.insert(0, parse_quote!(#lifetime));
+        let args = generic_args(&self.item.generics);
+
+        let (kind, mut_token, ref_token, method) = if mutable {
+            (
+                "mutable reference",
+                quote!(mut),
+                quote!(ref mut),
+                quote!(as_mut),
+            )
+        } else {
+            ("reference", quote!(), quote!(ref), quote!(as_ref))
+        };
+        // A derive would require `Clone` and `Copy` for the type parameters, but the view only holds a reference.
+        let copy = (!mutable).then(|| {
+            let generics = Generics {
+                params: params.clone(),
+                ..self.item.generics.clone()
+            };
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                #[allow(clippy::expl_impl_clone_on_copy)]
+                impl #impl_generics ::core::clone::Clone for #view #ty_generics #where_clause {
+                    fn clone(&self) -> Self {
+                        *self
+                    }
+                }
+
+                impl #impl_generics ::core::marker::Copy for #view #ty_generics #where_clause {}
+            }
+        });
+
+        let vars = self.variants();
+        let view_variants = vars.map(|var| {
+            let ident = &var.ident;
+            let ty = &var.fields.iter().next().unwrap().ty;
+            let doc = format!("A {kind} to the `{}` variant.", ident.unraw());
+            quote! {
+                #[doc = #doc]
+                #ident(&#lifetime #mut_token #ty)
+            }
+        });
+        let idents = self.item.variants.iter().map(|var| &var.ident);
+
+        let doc = format!("A {kind} to a variant of the `{e}` enum.");
+        let method_doc = format!("Get a {kind} to the current variant as [`{view}`].");
+        quote! {
+            #[doc = #doc]
+            #vis enum #view<#params> #where_clause {
+                #(#view_variants,)*
+            }
+
+            #copy
+
+            impl #impl_generics #e #ty_generics #where_clause {
+                #[doc = #method_doc]
+                #[allow(dead_code)]
+                #vis fn #method(&#mut_token self) -> #view<'_, #(#args),*> {
+                    match *self {
+                        #(#e::#idents(#ref_token v) => #view::#idents(v),)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get a lifetime that is not used by the enum generics.
+    fn fresh_lifetime(&self) -> Lifetime {
+        let lifetimes: HashSet<_> = self
+            .item
+            .generics
+            .lifetimes()
+            .map(|param| param.lifetime.ident.to_string())
+            .collect();
+        let name = ('a'..='z')
+            .map(String::from)
+            .find(|name| !lifetimes.contains(name))
+            .unwrap_or_else(|| String::from("__newtype_enum"));
+        Lifetime::new(&format!("'{name}"), Span::call_site())
+    }
+
+    /// Implement the `From` and `TryFrom` traits between the enum and its variants.
+    fn implement_conversions(&self) -> TokenStream {
+        if !self.from && !self.try_from {
+            return TokenStream::new();
+        }
+        let e = &self.item.ident;
+        let crate_name = &self.crate_name;
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+
+        let vars = self.variants().zip(&self.options);
+        let impls = vars.filter(|(_, options)| !options.skip).map(|(var, _)| {
+            let ident = &var.ident;
+            let ty = &var.fields.iter().next().unwrap().ty;
+            let from = self.from.then(|| {
+                quote! {
+                    impl #impl_generics ::core::convert::From<#ty> for #e #ty_generics #where_clause {
+                        fn from(v: #ty) -> Self {
+                            #e::#ident(v)
+                        }
+                    }
+                }
+            });
+            let try_from = self.try_from.then(|| {
+                quote! {
+                    impl #impl_generics ::core::convert::TryFrom<#e #ty_generics> for #ty #where_clause {
+                        type Error = #crate_name::VariantMismatch<#e #ty_generics>;
+
+                        fn try_from(e: #e #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                            match e {
+                                #e::#ident(v) => ::core::result::Result::Ok(v),
+                                e => ::core::result::Result::Err(#crate_name::VariantMismatch::new::<Self>(e)),
+                            }
+                        }
+                    }
+                }
+            });
+            quote! {
+                #from
+                #try_from
+            }
+        });
+        quote! {
+            const _: () = {
+                #(#impls)*
+            };
+        }
+    }
+
+    /// Generate visitor traits with one method per variant and the `accept` methods to call them.
+    fn implement_visitor(&self) -> TokenStream {
+        let Some((vis, visitor)) = &self.visitor else {
+            return TokenStream::new();
+        };
+        let e = &self.item.ident;
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+        let generics = &self.item.generics;
+        let idents: Vec<_> = self.item.variants.iter().map(|var| &var.ident).collect();
+        let methods: Vec<_> = idents
+            .iter()
+            .map(|ident| {
+                let name = format!("visit_{}", snake_case(&ident.unraw().to_string()));
+                Ident::new(&name, ident.span())
+            })
+            .collect();
+        let types: Vec<_> = self
+            .variants()
+            .map(|var| var.fields.into_iter().next().unwrap().ty)
+            .collect();
+
+        let modes = [
+            ("", "a reference to the", quote!(&), quote!(ref), "accept"),
+            (
+                "Mut",
+                "a mutable reference to the",
+                quote!(&mut),
+                quote!(ref mut),
+                "accept_mut",
+            ),
+            ("Owned", "the", quote!(), quote!(), "accept_owned"),
+        ];
+        let items = modes.iter().map(|(suffix, kind, ty_ref, pat_ref, accept)| {
+            let visitor = ident_append(visitor, suffix);
+            let (receiver, scrutinee) = if ty_ref.is_empty() {
+                (quote!(self), quote!(self))
+            } else {
+                (quote!(#ty_ref self), quote!(*self))
+            };
+            let accept = Ident::new(accept, Span::call_site());
+            let visit_methods = idents.iter().zip(&methods).zip(&types).map(|((ident, method), ty)| {
+                let doc = format!("Visit {kind} `{}` variant.", ident.unraw());
+                quote! {
+                    #[doc = #doc]
+                    fn #method(&mut self, v: #ty_ref #ty) -> Self::Output;
+                }
+            });
+            let doc = format!("A visitor for {kind} variants of the `{e}` enum.");
+            let accept_doc = format!("Call the method of the [`{visitor}`] for the current variant.");
+            quote! {
+                #[doc = #doc]
+                #[allow(clippy::ptr_arg)]
+                #vis trait #visitor #generics #where_clause {
+                    /// The output of the visitor.
+                    type Output;
+
+                    #(#visit_methods)*
+                }
+
+                impl #impl_generics #e #ty_generics #where_clause {
+                    #[doc = #accept_doc]
+                    #[allow(dead_code)]
+                    #vis fn #accept<__V: #visitor #ty_generics + ?Sized>(#receiver, visitor: &mut __V) -> __V::Output {
+                        match #scrutinee {
+                            #(#e::#idents(#pat_ref v) => visitor.#methods(v),)*
+                        }
+                    }
+                }
+            }
+        });
+        quote!(#(#items)*)
+    }
+
+    /// Generate the `match_with` method with one closure per variant in declaration order.
+    fn implement_match_with(&self) -> TokenStream {
+        if !self.match_with {
+            return TokenStream::new();
+        }
+        let e = &self.item.ident;
+        let vis = &self.item.vis;
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+        let idents: Vec<_> = self.item.variants.iter().map(|var| &var.ident).collect();
+        let closures: Vec<_> = idents
+            .iter()
+            .map(|ident| {
+                let name = format!("on_{}", snake_case(&ident.unraw().to_string()));
+                Ident::new(&name, ident.span())
+            })
+            .collect();
+        let types = self
+            .variants()
+            .map(|var| var.fields.into_iter().next().unwrap().ty);
+        quote! {
+            impl #impl_generics #e #ty_generics #where_clause {
+                /// Call the closure of the current variant (one closure per variant in declaration order).
+                #[allow(dead_code, clippy::too_many_arguments)]
+                #vis fn match_with<__R>(
+                    self,
+                    #(#closures: impl ::core::ops::FnOnce(#types) -> __R,)*
+                ) -> __R {
+                    match self {
+                        #(#e::#idents(v) => #closures(v),)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Implement `From` and `TryFrom` between the enum and the enums it is a subset of.
+    ///
+    /// The conversions use the `Variant` implementations of the superset, so every variant type of
+    /// the subset must be a variant type of the superset.
+    fn implement_subsets(&self) -> TokenStream {
+        if self.subset_of.is_empty() {
+            return TokenStream::new();
+        }
+        let e = &self.item.ident;
+        let crate_name = &self.crate_name;
+        let (impl_generics, ty_generics, where_clause) = self.item.generics.split_for_impl();
+        let idents: Vec<_> = self.item.variants.iter().map(|var| &var.ident).collect();
+        let types: Vec<_> = self
+            .variants()
+            .map(|var| var.fields.into_iter().next().unwrap().ty)
+            .collect();
+
+        let impls = self.subset_of.iter().map(|sup| {
+            quote! {
+                impl #impl_generics ::core::convert::From<#e #ty_generics> for #sup #where_clause {
+                    fn from(e: #e #ty_generics) -> Self {
+                        match e {
+                            #(#e::#idents(v) => <#types as #crate_name::unstable::VariantCore<#sup>>::into_enum(v),)*
+                        }
+                    }
+                }
+
+                impl #impl_generics ::core::convert::TryFrom<#sup> for #e #ty_generics #where_clause {
+                    type Error = #sup;
+
+                    fn try_from(e: #sup) -> ::core::result::Result<Self, Self::Error> {
+                        #(
+                            let e = match <#types as #crate_name::unstable::VariantCore<#sup>>::try_from_enum(e) {
+                                ::core::result::Result::Ok(v) => return ::core::result::Result::Ok(#e::#idents(v)),
+                                ::core::result::Result::Err(e) => e,
+                            };
+                        )*
+                        ::core::result::Result::Err(e)
+                    }
+                }
+            }
+        });
         quote! {
             const _: () = {
-                impl #crate_name::Enum for #e { }
                 #(#impls)*
             };
         }
     }
 }
 
+/// Implement `Deref`, `DerefMut` and `From` for a generated newtype struct.
+fn wrapper_impls(item: &ItemStruct) -> TokenStream {
+    let ident = &item.ident;
+    let ty = &item.fields.iter().next().unwrap().ty;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::core::ops::Deref for #ident #ty_generics #where_clause {
+            type Target = #ty;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl #impl_generics ::core::ops::DerefMut for #ident #ty_generics #where_clause {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#ty> for #ident #ty_generics #where_clause {
+            fn from(v: #ty) -> Self {
+                Self(v)
+            }
+        }
+    }
+}
+
+/// The arguments of the `#[newtype_enum]` attribute on an enum variant.
+#[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
+struct VariantOptions {
+    skip: bool,
+    wrap: bool,
+    flatten: bool,
+    default: bool,
+    rename: Option<Ident>,
+    borrow: Option<Type>,
+    no_borrow: bool,
+    serde_attrs: Vec<Meta>,
+}
+
+impl VariantOptions {
+    /// Parse and remove the `#[newtype_enum]`, `#[serde]` and `#[default]` attributes of the variant.
+    fn new(var: &mut Variant) -> Result<Self, Error> {
+        let mut options = Self {
+            serde_attrs: serde::take_attrs(&mut var.attrs)?,
+            ..Self::default()
+        };
+        var.attrs.retain(|attr| {
+            let default = attr.path().is_ident("default");
+            options.default |= default;
+            !default
+        });
+        let (attrs, other_attrs) = std::mem::take(&mut var.attrs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|attr| attr.path().is_ident("newtype_enum"));
+        var.attrs = other_attrs;
+
+        for attr in attrs {
+            attr.parse_nested_meta(|meta| {
+                let ident = meta
+                    .path
+                    .get_ident()
+                    .ok_or_else(|| Error::new_spanned(&meta.path, "expected identifier"))?;
+                if ident == "skip" {
+                    options.skip = true;
+                } else if ident == "rename" {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    options.rename = Some(lit.parse()?);
+                } else if ident == "borrow" {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    options.borrow = Some(lit.parse()?);
+                } else if ident == "no_borrow" {
+                    options.no_borrow = true;
+                } else if ident == "wrap" || ident == "flatten" {
+                    if !matches!(&var.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+                    {
+                        return Err(Error::new_spanned(ident, "expected a newtype variant"));
+                    }
+                    if ident == "wrap" {
+                        options.wrap = true;
+                    } else {
+                        options.flatten = true;
+                    }
+                } else {
+                    return Err(Error::new_spanned(ident, "unknown argument"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
+
 fn super_vis(vis: &Visibility, default: impl FnOnce() -> Visibility) -> Result<Visibility, Error> {
     let vis = match vis {
         Visibility::Inherited => default(),
@@ -263,6 +1210,168 @@ fn super_vis(vis: &Visibility, default: impl FnOnce() -> Visibility) -> Result<V
     Ok(vis)
 }
 
+/// Get the visibility of the re-export of a hidden `macro_rules!` macro.
+///
+/// `macro_rules!` macros can only be re-exported inside the crate.
+fn macro_reexport_vis(vis: &Visibility) -> Visibility {
+    match vis {
+        Visibility::Public(_) => parse_quote!(pub(crate)),
+        vis => vis.clone(),
+    }
+}
+
+/// Get the generic arguments to name an item with the generic parameters (without bounds and defaults).
+fn generic_args(generics: &Generics) -> impl Iterator<Item = TokenStream> + '_ {
+    generics.params.iter().map(|param| match param {
+        GenericParam::Type(param) => param.ident.to_token_stream(),
+        GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+        GenericParam::Const(param) => param.ident.to_token_stream(),
+    })
+}
+
+/// Order visibilities from private to public.
+///
+/// Restricted visibilities other than `pub(crate)` and `pub(self)` are treated as equal.
+fn vis_rank(vis: &Visibility) -> u8 {
+    match vis {
+        Visibility::Inherited => 0,
+        Visibility::Restricted(VisRestricted { path, .. }) if path.is_ident("self") => 0,
+        Visibility::Restricted(VisRestricted { path, .. }) if path.is_ident("crate") => 2,
+        Visibility::Restricted(_) => 1,
+        Visibility::Public(_) => 3,
+    }
+}
+
+/// Collect the generic parameters of an item that are used in a syntax tree.
+struct UsedGenerics {
+    params: HashSet<Ident>,
+    lifetimes: HashSet<Ident>,
+    used: HashSet<Ident>,
+    used_lifetimes: HashSet<Ident>,
+}
+
+impl UsedGenerics {
+    fn new(generics: &Generics) -> Self {
+        let mut params = HashSet::new();
+        let mut lifetimes = HashSet::new();
+        for param in &generics.params {
+            match param {
+                GenericParam::Type(param) => params.insert(param.ident.clone()),
+                GenericParam::Const(param) => params.insert(param.ident.clone()),
+                GenericParam::Lifetime(param) => lifetimes.insert(param.lifetime.ident.clone()),
+            };
+        }
+        Self {
+            params,
+            lifetimes,
+            used: HashSet::new(),
+            used_lifetimes: HashSet::new(),
+        }
+    }
+
+    fn contains(&self, param: &GenericParam) -> bool {
+        match param {
+            GenericParam::Type(param) => self.used.contains(&param.ident),
+            GenericParam::Const(param) => self.used.contains(&param.ident),
+            GenericParam::Lifetime(param) => self.used_lifetimes.contains(&param.lifetime.ident),
+        }
+    }
+
+    /// Check if any lifetime parameter is used.
+    fn uses_lifetimes(&self) -> bool {
+        !self.used_lifetimes.is_empty()
+    }
+
+    /// Check if a syntax tree only references parameters that are already used.
+    fn covers(&self, visit: impl FnOnce(&mut Self)) -> bool {
+        let mut other = Self {
+            params: self.params.clone(),
+            lifetimes: self.lifetimes.clone(),
+            used: HashSet::new(),
+            used_lifetimes: HashSet::new(),
+        };
+        visit(&mut other);
+        other.used.is_subset(&self.used) && other.used_lifetimes.is_subset(&self.used_lifetimes)
+    }
+}
+
+impl<'ast> Visit<'ast> for UsedGenerics {
+    fn visit_path(&mut self, path: &'ast Path) {
+        if path.leading_colon.is_none() {
+            if let Some(segment) = path.segments.first() {
+                if self.params.contains(&segment.ident) {
+                    self.used.insert(segment.ident.clone());
+                }
+            }
+        }
+        visit::visit_path(self, path);
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        if self.lifetimes.contains(&lifetime.ident) {
+            self.used_lifetimes.insert(lifetime.ident.clone());
+        }
+    }
+}
+
+/// Get the primitive integer type of the `#[repr]` attributes of the enum (including the `enum_attrs`).
+fn primitive_repr(attrs: &[Attribute]) -> Result<Option<Ident>, Error> {
+    const PRIMITIVES: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    let mut repr = None;
+    for attr in attrs {
+        if attr.path().is_ident("repr") {
+            let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            for meta in metas {
+                if let Meta::Path(path) = meta {
+                    if let Some(ident) = path.get_ident() {
+                        if PRIMITIVES.iter().any(|primitive| ident == primitive) {
+                            repr = Some(ident.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(repr)
+}
+
+/// Parse an identifier with an optional visibility prefix (e.g. `"pub(crate) name"`).
+fn parse_vis_ident(lit: &LitStr) -> Result<(Option<Visibility>, Ident), Error> {
+    lit.parse_with(|input: ParseStream| {
+        let vis = match input.parse()? {
+            Visibility::Inherited => None,
+            vis => Some(vis),
+        };
+        Ok((vis, input.parse()?))
+    })
+}
+
+/// Convert an `UpperCamelCase` name into `snake_case`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<_> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let word_start = prev.is_some_and(|prev| {
+                prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            });
+            if word_start {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
 fn ident_append(ident: &Ident, suffix: &str) -> Ident {
     Ident::new(&format!("{ident}{suffix}"), ident.span())
 }
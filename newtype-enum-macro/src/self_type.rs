@@ -0,0 +1,106 @@
+//! Resolution of `Self` in the variant fields and the generics of the enum.
+//!
+//! The fields are moved to generated structs, where `Self` would refer to the struct instead of
+//! the enum. The generic bounds are passed to the generated structs as well.
+
+use proc_macro2::{TokenStream, TokenTree};
+use syn::{
+    parse_quote,
+    visit_mut::{self, VisitMut},
+    Error, Expr, ExprPath, ItemEnum, Macro, Path, QSelf, Type, TypePath,
+};
+
+/// Replace `Self` with the enum type in the fields and the generics of the enum.
+///
+/// # Errors
+/// Returns an error if `Self` is used inside a macro invocation.
+pub fn resolve(item: &mut ItemEnum) -> Result<(), Error> {
+    let ident = &item.ident;
+    let (_, ty_generics, _) = item.generics.split_for_impl();
+    let mut visitor = ResolveSelf {
+        ty: parse_quote!(#ident #ty_generics),
+        error: None,
+    };
+    visitor.visit_generics_mut(&mut item.generics);
+    for var in &mut item.variants {
+        visitor.visit_fields_mut(&mut var.fields);
+    }
+    visitor.error.map_or(Ok(()), Err)
+}
+
+struct ResolveSelf {
+    ty: Type,
+    error: Option<Error>,
+}
+
+impl ResolveSelf {
+    fn check_macro(&mut self, mac: &Macro) {
+        if self.error.is_none() && contains_self(mac.tokens.clone()) {
+            self.error = Some(Error::new_spanned(
+                mac,
+                "cannot resolve `Self` inside a macro invocation, use the enum type instead",
+            ));
+        }
+    }
+}
+
+/// Check if the path starts with `Self`.
+fn starts_with_self(qself: Option<&QSelf>, path: &Path) -> bool {
+    qself.is_none()
+        && path.leading_colon.is_none()
+        && path
+            .segments
+            .first()
+            .is_some_and(|segment| segment.ident == "Self")
+}
+
+fn contains_self(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "Self",
+        TokenTree::Group(group) => contains_self(group.stream()),
+        _ => false,
+    })
+}
+
+impl VisitMut for ResolveSelf {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(TypePath { qself, path }) = ty {
+            if starts_with_self(qself.as_ref(), path) {
+                let enum_ty = &self.ty;
+                let segments = path.segments.iter().skip(1);
+                *ty = if path.segments.len() == 1 {
+                    enum_ty.clone()
+                } else {
+                    parse_quote!(<#enum_ty> #(::#segments)*)
+                };
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Path(ExprPath { qself, path, .. }) = expr {
+            if starts_with_self(qself.as_ref(), path) {
+                if path.segments.len() == 1 {
+                    self.error.get_or_insert_with(|| {
+                        Error::new_spanned(
+                            &*path,
+                            "cannot use `Self` as a value in a variant field",
+                        )
+                    });
+                } else {
+                    let enum_ty = &self.ty;
+                    let segments = path.segments.iter().skip(1);
+                    *expr = parse_quote!(<#enum_ty> #(::#segments)*);
+                }
+                return;
+            }
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        self.check_macro(mac);
+    }
+}
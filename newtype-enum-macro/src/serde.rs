@@ -0,0 +1,337 @@
+//! Routing of `#[serde]` attributes and generation of the `serde` implementations.
+
+use crate::{generic_args, NewtypeEnum, UsedGenerics};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{
+    ext::IdentExt, parse_quote, punctuated::Punctuated, visit::Visit, Attribute, Error, Fields,
+    GenericParam, Generics, Meta, Token, Type,
+};
+
+/// Parse and remove the `#[serde]` attributes.
+pub fn take_attrs(attrs: &mut Vec<Attribute>) -> Result<Vec<Meta>, Error> {
+    let (serde_attrs, other_attrs) = std::mem::take(attrs)
+        .into_iter()
+        .partition::<Vec<_>, _>(|attr| attr.path().is_ident("serde"));
+    *attrs = other_attrs;
+
+    let mut metas = Vec::new();
+    for attr in serde_attrs {
+        metas.extend(attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?);
+    }
+    Ok(metas)
+}
+
+/// Create a `#[serde]` attribute from the metas (if there are any).
+fn attr<'a>(metas: impl IntoIterator<Item = &'a Meta>) -> Option<Attribute> {
+    let metas: Vec<_> = metas.into_iter().collect();
+    (!metas.is_empty()).then(|| parse_quote!(#[serde(#(#metas),*)]))
+}
+
+fn is_any(meta: &Meta, names: &[&str]) -> bool {
+    names.iter().any(|name| meta.path().is_ident(name))
+}
+
+/// Get the `#[serde]` attribute of the enum.
+///
+/// `rename_all_fields` is only passed to the generated structs.
+pub fn enum_attr(metas: &[Meta]) -> Option<Attribute> {
+    attr(
+        metas
+            .iter()
+            .filter(|meta| !is_any(meta, &["rename_all_fields"])),
+    )
+}
+
+/// Get the `#[serde]` attribute of the enum, which is passed to the generated structs.
+///
+/// `rename_all_fields` is renamed to `rename_all`, because it applies to the fields of all struct variants.
+pub fn enum_struct_attr(metas: &[Meta]) -> Option<Attribute> {
+    let metas: Vec<Meta> = metas
+        .iter()
+        .filter(|meta| is_any(meta, &["rename_all_fields", "deny_unknown_fields"]))
+        .map(|meta| match meta {
+            Meta::NameValue(meta) if meta.path.is_ident("rename_all_fields") => {
+                let value = &meta.value;
+                parse_quote!(rename_all = #value)
+            }
+            meta => meta.clone(),
+        })
+        .collect();
+    attr(&metas)
+}
+
+/// Get the `#[serde]` attribute of a variant.
+///
+/// `rename_all` and `deny_unknown_fields` are only passed to the generated struct of the variant.
+pub fn variant_attr(metas: &[Meta], generated: bool) -> Option<Attribute> {
+    attr(
+        metas
+            .iter()
+            .filter(|meta| !generated || !is_any(meta, &["rename_all", "deny_unknown_fields"])),
+    )
+}
+
+/// Get the `#[serde]` attribute of a variant, which is passed to the generated struct.
+pub fn variant_struct_attr(metas: &[Meta]) -> Option<Attribute> {
+    attr(
+        metas
+            .iter()
+            .filter(|meta| is_any(meta, &["rename_all", "deny_unknown_fields"])),
+    )
+}
+
+impl NewtypeEnum {
+    /// Get the attributes to derive `Serialize` and `Deserialize` with the re-exported `serde` crate.
+    pub(crate) fn serde_derive(&self) -> Vec<Attribute> {
+        if !self.serde {
+            return Vec::new();
+        }
+        let crate_name = &self.crate_name;
+        let serde_crate = quote!(#crate_name::unstable::serde).to_string();
+        vec![
+            parse_quote!(#[derive(#crate_name::unstable::serde::Serialize, #crate_name::unstable::serde::Deserialize)]),
+            parse_quote!(#[serde(crate = #serde_crate)]),
+        ]
+    }
+
+    /// Check that no `#[serde]` attributes are used on the fields of newtype variants.
+    ///
+    /// The enum does not derive the `serde` traits itself, so these attributes would be ignored.
+    pub(crate) fn check_serde_fields(&self) -> Result<(), Error> {
+        let vars = self.item.variants.iter().zip(&self.options);
+        for (var, _) in vars.filter(|(var, options)| self.is_newtype(var, options)) {
+            for field in &var.fields {
+                if let Some(attr) = field
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path().is_ident("serde"))
+                {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "`serde` attributes are not supported on newtype variant fields, \
+                         use `#[newtype_enum(wrap)]` to pass them to a generated struct",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that internally tagged enums (`#[serde(tag = "...")]` without `content`) have no tuple variants.
+    ///
+    /// `serde` rejects them on the untransformed enum, but accepts the newtype variants of the
+    /// transformed enum and fails at runtime instead.
+    pub(crate) fn check_serde_tag(&self) -> Result<(), Error> {
+        let internally_tagged = self.serde_attrs.iter().any(|meta| is_any(meta, &["tag"]))
+            && !self
+                .serde_attrs
+                .iter()
+                .any(|meta| is_any(meta, &["content", "untagged"]));
+        if !internally_tagged {
+            return Ok(());
+        }
+        for var in &self.item.variants {
+            if let Fields::Unnamed(fields) = &var.fields {
+                if fields.unnamed.len() != 1 {
+                    return Err(Error::new_spanned(
+                        fields,
+                        "#[serde(tag = \"...\")] cannot be used with tuple variants",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Implement `Serialize` and `Deserialize` for the enum.
+    ///
+    /// Two private enums with the original variant shapes derive the `serde` traits,
+    /// so the enum uses the same representation as if it was not transformed.
+    pub(crate) fn implement_serde(&self) -> TokenStream {
+        if !self.serde {
+            return TokenStream::new();
+        }
+        let e = &self.item.ident;
+        let crate_name = &self.crate_name;
+        let serde = quote!(#crate_name::unstable::serde);
+        let (_, ty_generics, _) = self.item.generics.split_for_impl();
+        let args: Vec<_> = generic_args(&self.item.generics).collect();
+
+        let vars = self.serde_variants();
+        let shadow_enums = self.serde_shadow_enums(&serde, &vars);
+        let ser_arms = vars.iter().map(|SerdeVariant { ident, unit, .. }| {
+            if *unit {
+                quote!(#e::#ident(_) => __Serialize::#ident)
+            } else {
+                quote!(#e::#ident(ref v) => __Serialize::#ident(v))
+            }
+        });
+        let de_arms = vars.iter().map(
+            |SerdeVariant {
+                 ident, ty, unit, ..
+             }| {
+                if *unit {
+                    quote!(__Deserialize::#ident => #e::#ident(#ty))
+                } else {
+                    quote!(__Deserialize::#ident(v) => #e::#ident(v))
+                }
+            },
+        );
+
+        let (ser_generics, de_generics) = self.serde_generics(&serde);
+        let (ser_impl_generics, _, ser_where_clause) = ser_generics.split_for_impl();
+        let (de_impl_generics, _, de_where_clause) = de_generics.split_for_impl();
+
+        quote! {
+            const _: () = {
+                #shadow_enums
+
+                impl #ser_impl_generics #serde::Serialize for #e #ty_generics #ser_where_clause {
+                    fn serialize<__S: #serde::Serializer>(
+                        &self,
+                        serializer: __S,
+                    ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                        let v: __Serialize<'_, #(#args),*> = match *self {
+                            #(#ser_arms,)*
+                        };
+                        #serde::Serialize::serialize(&v, serializer)
+                    }
+                }
+
+                impl #de_impl_generics #serde::Deserialize<'de> for #e #ty_generics #de_where_clause {
+                    fn deserialize<__D: #serde::Deserializer<'de>>(
+                        deserializer: __D,
+                    ) -> ::core::result::Result<Self, __D::Error> {
+                        let v = <__Deserialize<#(#args),*> as #serde::Deserialize>::deserialize(deserializer)?;
+                        ::core::result::Result::Ok(match v {
+                            #(#de_arms,)*
+                        })
+                    }
+                }
+            };
+        }
+    }
+
+    /// Collect the variants of the private `serde` enums.
+    fn serde_variants(&self) -> Vec<SerdeVariant> {
+        let vars = self.variants().zip(&self.item.variants).zip(&self.options);
+        vars.map(|((var, orig), options)| {
+            let generated = !self.is_newtype(orig, options);
+            let ty = var.fields.into_iter().next().unwrap().ty;
+            let mut used = UsedGenerics::new(&self.item.generics);
+            used.visit_type(&ty);
+            SerdeVariant {
+                ident: var.ident,
+                ty,
+                unit: generated && matches!(orig.fields, Fields::Unit),
+                attr: variant_attr(&options.serde_attrs, generated),
+                borrow: used.uses_lifetimes(),
+            }
+        })
+        .collect()
+    }
+
+    /// Define the private enums `__Serialize` (holds references) and `__Deserialize` (holds values).
+    ///
+    /// Generated unit structs are converted back to unit variants.
+    fn serde_shadow_enums(&self, serde: &TokenStream, vars: &[SerdeVariant]) -> TokenStream {
+        let serde_crate = serde.to_string();
+        let lifetime = self.fresh_lifetime();
+        let params = &self.item.generics.params;
+        let where_clause = &self.item.generics.where_clause;
+
+        let enum_attr = enum_attr(&self.serde_attrs);
+        let has_rename = self
+            .serde_attrs
+            .iter()
+            .any(|meta| meta.path().is_ident("rename"));
+        let name = self.item.ident.unraw().to_string();
+        let rename = (!has_rename).then(|| quote!(#[serde(rename = #name)]));
+
+        let ser_variants = vars.iter().map(|var| {
+            let SerdeVariant {
+                ident, ty, attr, ..
+            } = var;
+            if var.unit {
+                quote!(#attr #ident)
+            } else {
+                quote!(#attr #ident(&#lifetime #ty))
+            }
+        });
+        let de_variants = vars.iter().map(|var| {
+            let SerdeVariant {
+                ident, ty, attr, ..
+            } = var;
+            let borrow = var.borrow.then(|| quote!(#[serde(borrow)]));
+            if var.unit {
+                quote!(#attr #ident)
+            } else {
+                quote!(#attr #ident(#borrow #ty))
+            }
+        });
+
+        quote! {
+            #[derive(#serde::Serialize)]
+            #[serde(crate = #serde_crate)]
+            #enum_attr
+            #rename
+            enum __Serialize<#lifetime, #params> #where_clause {
+                #(#ser_variants,)*
+                #[serde(skip)]
+                #[allow(dead_code)]
+                __Lifetime(::core::marker::PhantomData<&#lifetime ()>),
+            }
+
+            #[derive(#serde::Deserialize)]
+            #[serde(crate = #serde_crate)]
+            #enum_attr
+            #rename
+            enum __Deserialize<#params> #where_clause {
+                #(#de_variants,)*
+            }
+        }
+    }
+
+    /// Get the generics of the `Serialize` and `Deserialize` implementations.
+    ///
+    /// All type parameters must implement the trait and `'de` must outlive all lifetimes.
+    fn serde_generics(&self, serde: &TokenStream) -> (Generics, Generics) {
+        let mut ser_generics = self.item.generics.clone();
+        let mut de_generics = self.item.generics.clone();
+        de_generics.params.insert(0, parse_quote!('de));
+        for param in &self.item.generics.params {
+            match param {
+                GenericParam::Type(param) => {
+                    let ident = &param.ident;
+                    ser_generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!(#ident: #serde::Serialize));
+                    de_generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!(#ident: #serde::Deserialize<'de>));
+                }
+                GenericParam::Lifetime(param) => {
+                    let lifetime = &param.lifetime;
+                    de_generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!('de: #lifetime));
+                }
+                GenericParam::Const(_) => {}
+            }
+        }
+        (ser_generics, de_generics)
+    }
+}
+
+/// A variant of the private `serde` enums.
+struct SerdeVariant {
+    ident: Ident,
+    ty: Type,
+    unit: bool,
+    attr: Option<Attribute>,
+    borrow: bool,
+}
@@ -0,0 +1,106 @@
+//! Protection of the field types against shadowing by the generated structs.
+//!
+//! The generated module imports the scope of the enum with `use super::*`, so a generated struct
+//! (e.g. for a variant `String`) would shadow the type of the same name in the fields of all
+//! generated structs. Field types that use such a name are replaced by private type aliases,
+//! which are defined next to the enum, where the names are resolved as in the enum itself.
+
+use crate::{NewtypeEnum, UsedGenerics};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use std::collections::HashSet;
+use syn::{
+    ext::IdentExt,
+    parse_quote,
+    visit::{self, Visit},
+    Fields, GenericParam, Path,
+};
+
+impl NewtypeEnum {
+    /// Replace the field types, which use the name of a generated struct, with private aliases.
+    ///
+    /// Returns the definitions of the aliases.
+    pub(crate) fn alias_shadowed_fields(&self, ident: &Ident, fields: &mut Fields) -> TokenStream {
+        if self.layout.in_enum_scope() {
+            return TokenStream::new();
+        }
+        let names: HashSet<_> = self
+            .item
+            .variants
+            .iter()
+            .zip(&self.options)
+            .filter(|(var, options)| !self.is_newtype(var, options))
+            .map(|(var, options)| self.struct_ident(var, options))
+            .collect();
+
+        let mut aliases = Vec::new();
+        for (index, field) in fields.iter_mut().enumerate() {
+            let mut shadowed = Shadowed {
+                names: &names,
+                found: false,
+            };
+            shadowed.visit_type(&field.ty);
+            if !shadowed.found {
+                continue;
+            }
+
+            let alias = Ident::new(
+                &format!(
+                    "__newtype_enum_{}_{}_{index}",
+                    self.item.ident.unraw(),
+                    ident.unraw(),
+                ),
+                ident.span(),
+            );
+            let mut used = UsedGenerics::new(&self.item.generics);
+            used.visit_type(&field.ty);
+            // Bounds are not enforced on type aliases, so only the parameters are declared.
+            let (params, args): (Vec<_>, Vec<_>) = self
+                .item
+                .generics
+                .params
+                .iter()
+                .filter(|param| used.contains(param))
+                .map(|param| match param {
+                    GenericParam::Type(param) => {
+                        let ident = &param.ident;
+                        (quote!(#ident), quote!(#ident))
+                    }
+                    GenericParam::Lifetime(param) => {
+                        let lifetime = &param.lifetime;
+                        (quote!(#lifetime), quote!(#lifetime))
+                    }
+                    GenericParam::Const(param) => {
+                        let (ident, ty) = (&param.ident, &param.ty);
+                        (quote!(const #ident: #ty), quote!(#ident))
+                    }
+                })
+                .unzip();
+            let ty = &field.ty;
+            aliases.push(quote! {
+                #[doc(hidden)]
+                #[allow(non_camel_case_types)]
+                type #alias<#(#params),*> = #ty;
+            });
+            field.ty = parse_quote!(#alias<#(#args),*>);
+        }
+        quote!(#(#aliases)*)
+    }
+}
+
+/// Check if a type uses a path, which starts with one of the names.
+struct Shadowed<'a> {
+    names: &'a HashSet<Ident>,
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for Shadowed<'_> {
+    fn visit_path(&mut self, path: &'ast Path) {
+        if path.leading_colon.is_none() {
+            if let Some(segment) = path.segments.first() {
+                self.found |= self.names.contains(&segment.ident);
+            }
+        }
+        visit::visit_path(self, path);
+    }
+}
@@ -0,0 +1,92 @@
+//! Type-level lists of the variant types of an enum.
+//!
+//! The [`Variants`](../trait.Enum.html#associatedtype.Variants) of an [`Enum`](../trait.Enum.html) are a heterogeneous list of all its [`Variant`](../trait.Variant.html) types in declaration order, e.g. `HCons<A, HCons<B, HNil>>`.
+
+use crate::{Enum, Variant};
+use core::marker::PhantomData;
+
+/// The empty type-level list.
+pub struct HNil;
+
+/// A type-level list with the head `H` and the tail `T`.
+pub struct HCons<H, T>(PhantomData<fn() -> (H, T)>);
+
+/// A type-level list of [`Variant`](../trait.Variant.html) types of the enum `E`.
+///
+/// This trait is implemented for [`HNil`](struct.HNil.html) and [`HCons`](struct.HCons.html) if all elements are variants of `E`.
+pub trait VariantList<E: Enum> {
+    /// The number of variant types in the list.
+    const LEN: usize;
+
+    /// Call the visitor for every variant type in the list (in order).
+    fn for_each<F: VariantTypeVisitor<E> + ?Sized>(visitor: &mut F);
+}
+
+impl<E: Enum> VariantList<E> for HNil {
+    const LEN: usize = 0;
+
+    fn for_each<F: VariantTypeVisitor<E> + ?Sized>(_visitor: &mut F) {}
+}
+
+impl<E: Enum, H: Variant<E>, T: VariantList<E>> VariantList<E> for HCons<H, T> {
+    const LEN: usize = T::LEN + 1;
+
+    fn for_each<F: VariantTypeVisitor<E> + ?Sized>(visitor: &mut F) {
+        visitor.visit::<H>();
+        T::for_each(visitor);
+    }
+}
+
+/// A generic callback, which is called for every variant type of the enum `E`.
+///
+/// Use [`Enum::for_each_variant_type`](../trait.Enum.html#method.for_each_variant_type) to call it.
+///
+/// ```
+/// # #[newtype_enum::newtype_enum]
+/// # pub enum Test {
+/// #     Ping,
+/// #     Number(usize),
+/// #     Str(&'static str),
+/// # }
+/// # fn main() {
+/// use newtype_enum::{hlist::VariantTypeVisitor, Enum, Variant};
+///
+/// struct SizeOf(usize);
+///
+/// impl VariantTypeVisitor<Test> for SizeOf {
+///     fn visit<V: Variant<Test>>(&mut self) {
+///         self.0 = self.0.max(core::mem::size_of::<V>());
+///     }
+/// }
+///
+/// let mut size_of = SizeOf(0);
+/// Test::for_each_variant_type(&mut size_of);
+/// assert_eq!(size_of.0, core::mem::size_of::<&str>());
+/// # }
+/// ```
+pub trait VariantTypeVisitor<E: Enum> {
+    /// Visit the variant type `V`.
+    fn visit<V: Variant<E>>(&mut self);
+}
+
+/// The type-level index of the head of a list (used by [`Pluck`](trait.Pluck.html)).
+pub struct Here;
+
+/// The type-level index of an element in the tail of a list (used by [`Pluck`](trait.Pluck.html)).
+pub struct There<I>(PhantomData<fn() -> I>);
+
+/// Remove the type `V` at the index `I` from a type-level list.
+///
+/// The index is inferred by the compiler, if `V` is contained exactly once in the list.
+pub trait Pluck<V, I> {
+    /// The list without `V`.
+    type Rest;
+}
+
+impl<V, T> Pluck<V, Here> for HCons<V, T> {
+    type Rest = T;
+}
+
+impl<V, I, H, T: Pluck<V, I>> Pluck<V, There<I>> for HCons<H, T> {
+    type Rest = HCons<H, T::Rest>;
+}
@@ -46,6 +46,16 @@
 //!     Example(usize),
 //! }
 //! ```
+//!
+//! The wrapped type must uniquely identify the variant, so two newtype variants cannot wrap the same type (see [`wrap`](#wrap-newtype-variants) and [`skip`](#skip-the-variant-implementation)):
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum]
+//! enum Test {
+//!     Min(usize),
+//!     Max(usize), // error: the wrapped type `usize` is already used by the variant `Min`
+//! }
+//! ```
 //! ## Struct variants
 //! ```
 //! # use newtype_enum::newtype_enum;
@@ -66,6 +76,241 @@
 //! }
 //! ```
 //!
+//! ## Tuple variants
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum]
+//! enum Test {
+//!     Example(usize, usize),
+//! }
+//! ```
+//! ```
+//! enum Test {
+//!     Example(Test_variants::Example),
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct Example(pub(super) usize, pub(super) usize);
+//! }
+//! ```
+//!
+//! ## Generic enums
+//! The generic parameters of the enum are passed to the generated structs. Every struct only gets the parameters (and their bounds) that are used by its fields.
+//!
+//! Newtype variants that wrap a bare type parameter (or a reference, `Box` or `Pin` of it) are converted to generated tuple structs (see [`wrap`](#wrap-newtype-variants)), because the wrapped type could not uniquely identify the variant.
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum]
+//! enum Test<'a, T: Clone> {
+//!     Example(T),
+//!     List(Vec<T>),
+//!     Borrowed { test: &'a str },
+//!     Empty,
+//! }
+//! ```
+//! ```
+//! enum Test<'a, T: Clone> {
+//!     Example(Test_variants::Example<T>),
+//!     List(Vec<T>),
+//!     Borrowed(Test_variants::Borrowed<'a>),
+//!     Empty(Test_variants::Empty),
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct Example<T: Clone>(pub(super) T);
+//!
+//!     pub(super) struct Borrowed<'a> {
+//!         pub(super) test: &'a str,
+//!     }
+//!
+//!     pub(super) struct Empty;
+//! }
+//! ```
+//!
+//! ## Recursive enums
+//! `Self` in the variant fields is replaced with the enum type, because it would refer to the generated struct otherwise. `Self` cannot be used inside macro invocations in the variant fields.
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum]
+//! enum Tree<T> {
+//!     Node { children: Vec<Self> },
+//!     Leaf(T),
+//! }
+//! # fn main() {}
+//! ```
+//! ```
+//! enum Tree<T> {
+//!     Node(Tree_variants::Node<T>),
+//!     Leaf(Tree_variants::Leaf<T>), // the bare type parameter is wrapped (see above)
+//! }
+//!
+//! mod Tree_variants {
+//!     use super::*;
+//!
+//!     pub(super) struct Node<T> {
+//!         pub(super) children: Vec<Tree<T>>,
+//!     }
+//!
+//!     pub(super) struct Leaf<T>(pub(super) T);
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Shadowed names
+//! The generated structs do not shadow the types of the same name in the variant fields. Field types, which use the name of a generated struct, are replaced by private type aliases next to the enum. The aliases are hidden from the documentation, which shows the original field types instead. Inline and re-exported variants (see below) are visible in the scope of the enum and can shadow types as usual.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[derive(Debug, PartialEq)]
+//! struct Error(&'static str);
+//!
+//! #[newtype_enum]
+//! #[derive(Debug, PartialEq)]
+//! enum Test {
+//!     String {
+//!         value: String,
+//!     },
+//!     Error {
+//!         error: Error,
+//!         message: Option<String>,
+//!     },
+//!     Name(String),
+//! }
+//!
+//! # fn main() {
+//! let test = Test::from_variant(Test_variants::String {
+//!     value: "a".to_string(),
+//! });
+//! assert_eq!(
+//!     test.into_variant(),
+//!     Some(Test_variants::String {
+//!         value: "a".to_string()
+//!     })
+//! );
+//!
+//! let error = Test_variants::Error {
+//!     error: Error("b"),
+//!     message: None,
+//! };
+//! assert_eq!(error.error, Error("b"));
+//! assert_eq!(
+//!     Test::from_variant("c".to_string()),
+//!     Test::Name("c".to_string())
+//! );
+//! # }
+//! ```
+//! ```
+//! # #[derive(Debug, PartialEq)]
+//! # struct Error(&'static str);
+//! enum Test {
+//!     String(Test_variants::String),
+//!     Error(Test_variants::Error),
+//!     Name(String),
+//! }
+//!
+//! #[doc(hidden)]
+//! type __newtype_enum_Test_String_0 = String;
+//! #[doc(hidden)]
+//! type __newtype_enum_Test_Error_0 = Error;
+//! #[doc(hidden)]
+//! type __newtype_enum_Test_Error_1 = Option<String>;
+//!
+//! mod Test_variants {
+//!     use super::*;
+//!
+//!     pub(super) struct String {
+//!         pub(super) value: __newtype_enum_Test_String_0,
+//!     }
+//!
+//!     pub(super) struct Error {
+//!         pub(super) error: __newtype_enum_Test_Error_0,
+//!         pub(super) message: __newtype_enum_Test_Error_1,
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Explicit discriminants
+//! Explicit discriminants are kept on the enum variants. This requires a primitive representation (e.g. `#[repr(u8)]`) of the enum. The `#[repr]` attribute is not passed to the generated structs.
+//!
+//! With the `discriminant` argument the macro generates a `discriminant` method, which returns the discriminant of the current variant as the primitive type.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum(discriminant)]
+//! #[repr(u8)]
+//! enum Test {
+//!     Example = 3,
+//!     Number(usize) = 42,
+//! }
+//!
+//! assert_eq!(Test::from_variant(Test_variants::Example).discriminant(), 3);
+//! assert_eq!(Test::from_variant(123).discriminant(), 42);
+//! ```
+//! ```
+//! #[repr(u8)]
+//! enum Test {
+//!     Example(Test_variants::Example) = 3,
+//!     Number(usize) = 42,
+//! }
+//!
+//! impl Test {
+//!     fn discriminant(&self) -> u8 {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct Example;
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum]
+//! enum Test {
+//!     Example = 3, // error: explicit discriminants require a primitive representation
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(discriminant)] // error: the `discriminant` argument requires a primitive representation
+//! enum Test {
+//!     Example,
+//! }
+//! ```
+//!
+//! Without the `discriminant` argument the enum can define its own `discriminant` method:
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum]
+//! #[repr(u8)]
+//! enum Test {
+//!     Example = 3,
+//!     Number(usize) = 42,
+//! }
+//!
+//! impl Test {
+//!     fn discriminant(&self) -> &'static str {
+//!         "custom"
+//!     }
+//! }
+//!
+//! assert_eq!(Test::from_variant(123).discriminant(), "custom");
+//! ```
+//!
+//! The representation can also be set with the `enum_attrs` argument:
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum(discriminant, enum_attrs(repr(u8)))]
+//! enum Test {
+//!     Example = 3,
+//!     Number(usize) = 42,
+//! }
+//!
+//! assert_eq!(Test::from_variant(123).discriminant(), 42);
+//! ```
+//!
 //! # Attribute arguments
 //! You can pass the following arguments to the `newtype_enum` macro:
 //!
@@ -78,32 +323,889 @@
 //! }
 //! ```
 //! ```
-//! enum Test {
-//!     Example(test::Example),
+//! enum Test {
+//!     Example(test::Example),
+//! }
+//!
+//! mod test {
+//!     // <-- the name of the generated module
+//!     pub(super) struct Example;
+//! }
+//! ```
+//! ## Variants module visibility
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(variants = "pub(crate) test")]
+//! enum Test {
+//!     Example,
+//! }
+//! ```
+//! ```
+//! enum Test {
+//!     Example(test::Example),
+//! }
+//!
+//! pub(crate) mod test {
+//!     // <-- the visibility of the generated module
+//!     pub(super) struct Example;
+//! }
+//! ```
+//!
+//! ## Inline variants
+//! Define the generated structs next to the enum instead of in a module. This allows enums in function bodies and other local scopes, where the generated module could not access local types. It cannot be combined with the `variants` and `reexport` arguments.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! fn test() {
+//!     struct Local;
+//!
+//!     #[newtype_enum(inline)]
+//!     enum Test {
+//!         Example { local: Local },
+//!     }
+//!
+//!     let test = Test::from_variant(Example { local: Local });
+//!     assert!(test.is_variant::<Example>());
+//! }
+//! # test();
+//! ```
+//! ```
+//! fn test() {
+//!     struct Local;
+//!
+//!     enum Test {
+//!         Example(Example),
+//!     }
+//!
+//!     struct Example {
+//!         local: Local,
+//!     }
+//! }
+//! ```
+//!
+//! The generated structs cannot have the same name as the enum, use [`rename`](#rename-the-generated-struct) to choose another name:
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(inline)]
+//! enum Test {
+//!     Test { value: usize }, // error: the struct name `Test` is already used by the enum
+//! }
+//! ```
+//!
+//! ## Re-exported variants
+//! Import the generated structs next to the enum with the visibility of the enum. The structs are still defined in the variants module.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum(reexport)]
+//! enum Test {
+//!     Example,
+//! }
+//!
+//! # fn main() {
+//! let test = Test::from_variant(Example);
+//! assert!(test.is_variant::<Test_variants::Example>());
+//! # }
+//! ```
+//! ```
+//! enum Test {
+//!     Example(Test_variants::Example),
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct Example;
+//! }
+//!
+//! use Test_variants::Example;
+//! # fn main() {}
+//! ```
+//!
+//! ## Struct names
+//! Add a prefix or a suffix to the names of all generated structs, e.g. to glob-import the variants modules of several enums. The names of the enum variants are not changed. See [`rename`](#rename-the-generated-struct) to set the name of a single struct.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum(struct_prefix = "Test", struct_suffix = "Msg")]
+//! enum Test {
+//!     Example,
+//! }
+//!
+//! let test = Test::from_variant(Test_variants::TestExampleMsg);
+//! assert!(matches!(test, Test::Example(_)));
+//! ```
+//! ```
+//! enum Test {
+//!     Example(Test_variants::TestExampleMsg),
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct TestExampleMsg;
+//! }
+//! ```
+//!
+//! ## Kind enum
+//! Generate a fieldless enum with one variant per enum variant, a `kind` method to get the kind of the current variant and implement the [`EnumKind`](trait.EnumKind.html) and [`VariantKind`](trait.VariantKind.html) traits. The visibility can be set in the same way as for the variants module, but it must not be narrower than the visibility of the enum, because the kind enum is part of the public interface of the enum.
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(kind = "TestKind")]
+//! enum Test {
+//!     Example,
+//!     Number(usize),
+//! }
+//!
+//! use newtype_enum::{Enum, VariantKind};
+//!
+//! assert_eq!(Test::from_variant(123).kind(), TestKind::Number);
+//! assert_eq!(<usize as VariantKind<Test>>::KIND, TestKind::Number);
+//! ```
+//! ```
+//! enum Test {
+//!     Example(Test_variants::Example),
+//!     Number(usize),
+//! }
+//!
+//! #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+//! enum TestKind {
+//!     Example,
+//!     Number,
+//! }
+//!
+//! impl Test {
+//!     fn kind(&self) -> TestKind {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct Example;
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(kind = "pub(crate) TestKind")] // error: the visibility of the kind enum must not be narrower than the visibility of the enum
+//! pub enum Test {
+//!     Example,
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Reference enums
+//! Generate enums with the same variants that hold a reference (`ref_enum`) or a mutable reference (`mut_enum`) to the variant and an `as_ref` or `as_mut` method to convert the enum. The visibility can be set in the same way as for the variants module.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum(ref_enum = "TestRef", mut_enum = "TestMut")]
+//! enum Test {
+//!     Example { test: usize },
+//!     Number(usize),
+//! }
+//!
+//! let mut test = Test::from_variant(Test_variants::Example { test: 123 });
+//! if let TestMut::Example(example) = test.as_mut() {
+//!     example.test = 42;
+//! }
+//! match test.as_ref() {
+//!     TestRef::Example(example) => assert_eq!(example.test, 42),
+//!     TestRef::Number(_) => unreachable!(),
+//! }
+//! ```
+//! ```
+//! enum Test {
+//!     Example(Test_variants::Example),
+//!     Number(usize),
+//! }
+//!
+//! #[derive(Clone, Copy)]
+//! enum TestRef<'a> {
+//!     Example(&'a Test_variants::Example),
+//!     Number(&'a usize),
+//! }
+//!
+//! enum TestMut<'a> {
+//!     Example(&'a mut Test_variants::Example),
+//!     Number(&'a mut usize),
+//! }
+//!
+//! impl Test {
+//!     fn as_ref(&self) -> TestRef<'_> {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//!
+//!     fn as_mut(&mut self) -> TestMut<'_> {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct Example {
+//!         pub(super) test: usize,
+//!     }
+//! }
+//! ```
+//!
+//! The reference enum is `Clone` and `Copy` for all type parameters, because it only holds a reference:
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum(ref_enum = "TestRef")]
+//! enum Test<T> {
+//!     Example(Vec<T>),
+//!     Number(usize),
+//! }
+//!
+//! let test = Test::<String>::from_variant(vec!["Tester".to_string()]);
+//! let (a, b) = (test.as_ref(), test.as_ref());
+//! let copy = a;
+//! assert!(matches!(
+//!     (a, b, copy),
+//!     (
+//!         TestRef::Example(_),
+//!         TestRef::Example(_),
+//!         TestRef::Example(_)
+//!     )
+//! ));
+//! ```
+//!
+//! ## Visitor traits
+//! Generate visitor traits with one `visit_<variant>` method per variant and the `accept` methods to call the method of the current variant.
+//! The variant is passed by reference (`accept`), by mutable reference (`accept_mut`) or by value (`accept_owned`).
+//! Every visitor must handle all variants, so adding a variant is a compile error for all existing visitors.
+//! The visibility can be set in the same way as for the variants module.
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(visitor = "TestVisitor")]
+//! enum Test {
+//!     Example { test: usize },
+//!     Number(usize),
+//! }
+//!
+//! struct Sum;
+//!
+//! impl TestVisitor for Sum {
+//!     type Output = usize;
+//!
+//!     fn visit_example(&mut self, v: &Test_variants::Example) -> usize {
+//!         v.test
+//!     }
+//!
+//!     fn visit_number(&mut self, v: &usize) -> usize {
+//!         *v
+//!     }
+//! }
+//!
+//! let test = Test::Example(Test_variants::Example { test: 123 });
+//! assert_eq!(test.accept(&mut Sum), 123);
+//! ```
+//! ```
+//! enum Test {
+//!     Example(Test_variants::Example),
+//!     Number(usize),
+//! }
+//!
+//! trait TestVisitor {
+//!     type Output;
+//!
+//!     fn visit_example(&mut self, v: &Test_variants::Example) -> Self::Output;
+//!     fn visit_number(&mut self, v: &usize) -> Self::Output;
+//! }
+//!
+//! trait TestVisitorMut {
+//!     type Output;
+//!
+//!     fn visit_example(&mut self, v: &mut Test_variants::Example) -> Self::Output;
+//!     fn visit_number(&mut self, v: &mut usize) -> Self::Output;
+//! }
+//!
+//! trait TestVisitorOwned {
+//!     type Output;
+//!
+//!     fn visit_example(&mut self, v: Test_variants::Example) -> Self::Output;
+//!     fn visit_number(&mut self, v: usize) -> Self::Output;
+//! }
+//!
+//! impl Test {
+//!     fn accept<V: TestVisitor + ?Sized>(&self, visitor: &mut V) -> V::Output {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//!
+//!     fn accept_mut<V: TestVisitorMut + ?Sized>(&mut self, visitor: &mut V) -> V::Output {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//!
+//!     fn accept_owned<V: TestVisitorOwned + ?Sized>(self, visitor: &mut V) -> V::Output {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct Example {
+//!         pub(super) test: usize,
+//!     }
+//! }
+//! ```
+//!
+//! The method names are the snake case names of the variants, which must be unique (this also applies to the closures of [`match_with`](#match-with-closures)):
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(visitor = "TestVisitor")]
+//! enum Test {
+//!     HTTPServer,
+//!     HttpServer, // error: the snake case name `http_server` of the variant is already used by the variant `HTTPServer`
+//! }
+//! ```
+//!
+//! ## Match with closures
+//! Generate a `match_with` method with one closure per variant in declaration order (`match_with`). Use [`Enum::matcher`](trait.Enum.html#method.matcher) to pass the closures by variant type instead.
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(match_with)]
+//! enum Test {
+//!     Example { test: usize },
+//!     Number(usize),
+//! }
+//!
+//! let test = Test::Number(123);
+//! assert_eq!(
+//!     test.match_with(|example| example.test, |number| number),
+//!     123
+//! );
+//! ```
+//! ```
+//! enum Test {
+//!     Example(Test_variants::Example),
+//!     Number(usize),
+//! }
+//!
+//! impl Test {
+//!     fn match_with<R>(
+//!         self,
+//!         on_example: impl FnOnce(Test_variants::Example) -> R,
+//!         on_number: impl FnOnce(usize) -> R,
+//!     ) -> R {
+//!         // ...
+//! #       unimplemented!()
+//!     }
+//! }
+//! # mod Test_variants {
+//! #     pub(super) struct Example;
+//! # }
+//! ```
+//!
+//! ## Trait delegation
+//! Implement a trait for the enum by forwarding every method to the current variant (`delegate(Trait)`). All variant types must implement the trait.
+//! The trait must be annotated with the [`delegate`](attr.delegate.html) attribute and it must be defined in the same crate.
+//! Methods without a `self` receiver need a default implementation, methods cannot have a typed receiver (e.g. `self: Box<Self>`) and methods cannot use `Self` in their arguments or return type; traits with associated types, associated constants or generic parameters are not supported.
+//! ```
+//! # use newtype_enum::{delegate, newtype_enum};
+//! #[delegate]
+//! trait Area {
+//!     fn area(&self) -> f64;
+//! }
+//!
+//! #[newtype_enum(delegate(Area))]
+//! enum Shape {
+//!     Square { width: f64 },
+//!     Circle { radius: f64 },
+//! }
+//!
+//! impl Area for Shape_variants::Square {
+//!     fn area(&self) -> f64 {
+//!         self.width * self.width
+//!     }
+//! }
+//!
+//! impl Area for Shape_variants::Circle {
+//!     fn area(&self) -> f64 {
+//!         core::f64::consts::PI * self.radius * self.radius
+//!     }
+//! }
+//!
+//! let shape = Shape::Square(Shape_variants::Square { width: 2.0 });
+//! assert_eq!(shape.area(), 4.0);
+//! ```
+//! ```
+//! # trait Area {
+//! #     fn area(&self) -> f64;
+//! # }
+//! enum Shape {
+//!     Square(Shape_variants::Square),
+//!     Circle(Shape_variants::Circle),
+//! }
+//!
+//! impl Area for Shape {
+//!     fn area(&self) -> f64 {
+//!         match self {
+//!             Shape::Square(v) => Area::area(v),
+//!             Shape::Circle(v) => Area::area(v),
+//!         }
+//!     }
+//! }
+//! # mod Shape_variants {
+//! #     pub(super) struct Square;
+//! #     pub(super) struct Circle;
+//! # }
+//! # impl Area for Shape_variants::Square { fn area(&self) -> f64 { 0.0 } }
+//! # impl Area for Shape_variants::Circle { fn area(&self) -> f64 { 0.0 } }
+//! ```
+//! The types in the method signatures of the trait must be in scope where the enum is defined. Wrapped newtype variants delegate to the wrapped type.
+//! The hidden macro of the trait is imported together with the trait and has the same visibility (at most `pub(crate)`).
+//! ```
+//! mod shapes {
+//!     mod traits {
+//!         use newtype_enum::delegate;
+//!
+//!         #[delegate]
+//!         pub(super) trait Area {
+//!             fn area(&self) -> f64;
+//!         }
+//!
+//!         #[delegate]
+//!         pub(in crate::shapes) trait Name {
+//!             fn name(&self) -> &'static str;
+//!         }
+//!     }
+//!
+//!     use newtype_enum::newtype_enum;
+//!     use traits::{Area, Name};
+//!
+//!     #[newtype_enum(delegate(Area, Name))]
+//!     pub(crate) enum Shape {
+//!         Square(f64),
+//!     }
+//!
+//!     impl Area for f64 {
+//!         fn area(&self) -> f64 {
+//!             self * self
+//!         }
+//!     }
+//!
+//!     impl Name for f64 {
+//!         fn name(&self) -> &'static str {
+//!             "square"
+//!         }
+//!     }
+//!
+//!     pub(crate) fn describe(shape: &Shape) -> (&'static str, f64) {
+//!         (shape.name(), shape.area())
+//!     }
+//! }
+//!
+//! # fn main() {
+//! assert_eq!(
+//!     shapes::describe(&shapes::Shape::Square(2.0)),
+//!     ("square", 4.0)
+//! );
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! # use newtype_enum::{delegate, newtype_enum};
+//! #[delegate]
+//! trait Merge {
+//!     fn merge(&self, other: &Self) -> Self; // error: cannot delegate a method with `Self` in its arguments or return type
+//! }
+//!
+//! #[newtype_enum(delegate(Merge))]
+//! enum Test {
+//!     Number(u8),
+//! }
+//! # impl Merge for u8 {
+//! #     fn merge(&self, other: &Self) -> Self {
+//! #         self + other
+//! #     }
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! # use newtype_enum::{delegate, newtype_enum};
+//! #[delegate]
+//! trait Consume {
+//!     fn consume(self: Box<Self>) -> u8; // error: cannot delegate a method with a typed `self` receiver
+//! }
+//!
+//! #[newtype_enum(delegate(Consume))]
+//! enum Test {
+//!     Number(u8),
+//! }
+//! # impl Consume for u8 {
+//! #     fn consume(self: Box<Self>) -> u8 {
+//! #         *self
+//! #     }
+//! # }
+//! ```
+//!
+//! ## Standard conversion traits
+//! Implement `From<V> for E` (`from`) and `TryFrom<E> for V` (`try_from`) for all variant types `V` of the enum `E`. The error type of `TryFrom` is [`VariantMismatch<E>`](struct.VariantMismatch.html).
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! use core::convert::{TryFrom, TryInto};
+//!
+//! #[newtype_enum(from, try_from)]
+//! # #[derive(Debug)]
+//! enum Test {
+//!     Number(usize),
+//!     Str(&'static str),
+//! }
+//!
+//! let test: Test = 123.into();
+//! assert_eq!(usize::try_from(test).unwrap(), 123);
+//!
+//! let test = Test::from("Hello World");
+//! let err = TryInto::<usize>::try_into(test).unwrap_err();
+//! assert_eq!(err.found(), "Str");
+//! ```
+//! ```
+//! # use newtype_enum::VariantMismatch;
+//! use core::convert::TryFrom;
+//!
+//! enum Test {
+//!     Number(usize),
+//!     Str(&'static str),
+//! }
+//!
+//! impl From<usize> for Test {
+//!     // ...
+//! #   fn from(v: usize) -> Self {
+//! #       Test::Number(v)
+//! #   }
+//! }
+//!
+//! impl TryFrom<Test> for usize {
+//!     type Error = VariantMismatch<Test>;
+//!     // ...
+//! #   fn try_from(e: Test) -> Result<Self, Self::Error> {
+//! #       unimplemented!()
+//! #   }
+//! }
+//!
+//! // impl From<&'static str> for Test, impl TryFrom<Test> for &'static str
+//! ```
+//!
+//! ## Subset enums
+//! Implement `From<E> for S` and `TryFrom<S> for E` for an enum `E` whose variant types are a subset of the variant types of the enum `S` (`subset_of = "S"`). The error type of `TryFrom` is `S`, which contains the variant that is not part of `E`.
+//! The argument can be repeated to declare multiple supersets.
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! use core::convert::TryFrom;
+//!
+//! # #[derive(Debug, PartialEq)]
+//! pub struct Get;
+//! # #[derive(Debug, PartialEq)]
+//! pub struct Put;
+//!
+//! #[newtype_enum]
+//! # #[derive(Debug, PartialEq)]
+//! enum Request {
+//!     Get(Get),
+//!     Put(Put),
+//! }
+//!
+//! #[newtype_enum(subset_of = "Request")]
+//! # #[derive(Debug, PartialEq)]
+//! enum ReadRequest {
+//!     Get(Get),
+//! }
+//!
+//! let request = Request::from(ReadRequest::Get(Get));
+//! assert_eq!(ReadRequest::try_from(request), Ok(ReadRequest::Get(Get)));
+//! assert_eq!(
+//!     ReadRequest::try_from(Request::Put(Put)),
+//!     Err(Request::Put(Put))
+//! );
+//! ```
+//! Every variant type of the subset must be a [`Variant`](trait.Variant.html) of the superset:
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum]
+//! enum Request {
+//!     Get(usize),
+//! }
+//!
+//! #[newtype_enum(subset_of = "Request")]
+//! enum ReadRequest {
+//!     Get(usize),
+//!     Head(&'static str),
+//! }
+//! ```
+//!
+//! ## Serde
+//! Implement `Serialize` and `Deserialize` for the enum and the generated variant structs (requires the `serde` feature).
+//! The enum has the same representation as the untransformed enum, so all enum representations (externally, internally and adjacently tagged) are supported.
+//! The tag is the name of the variant, unless it is renamed with `#[serde(rename = "...")]` or `#[serde(rename_all = "...")]`.
+//! As for the untransformed enum, internally tagged enums cannot have tuple variants.
+//! ```
+//! # #[cfg(feature = "serde")]
+//! # fn main() {
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(serde)]
+//! # #[derive(Debug, PartialEq)]
+//! #[serde(tag = "type", rename_all = "snake_case")]
+//! enum Message {
+//!     Ping,
+//!     Hello { name: String },
+//! }
+//!
+//! let json = r#"{"type":"hello","name":"World"}"#;
+//! let hello = Message::Hello(Message_variants::Hello {
+//!     name: "World".into(),
+//! });
+//! assert_eq!(serde_json::to_string(&hello).unwrap(), json);
+//! assert_eq!(serde_json::from_str::<Message>(json).unwrap(), hello);
+//!
+//! let json = r#"{"type":"ping"}"#;
+//! let ping = Message::Ping(Message_variants::Ping);
+//! assert_eq!(serde_json::to_string(&ping).unwrap(), json);
+//! assert_eq!(serde_json::from_str::<Message>(json).unwrap(), ping);
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+//! ```
+//! # #[cfg(feature = "serde")]
+//! # fn main() {
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(serde)]
+//! # #[derive(Debug, PartialEq)]
+//! #[serde(tag = "t", content = "c")]
+//! enum Message {
+//!     Ping,
+//!     Number(u32),
+//! }
+//!
+//! let json = r#"{"t":"Number","c":123}"#;
+//! assert_eq!(serde_json::to_string(&Message::Number(123)).unwrap(), json);
+//! assert_eq!(
+//!     serde_json::from_str::<Message>(json).unwrap(),
+//!     Message::Number(123)
+//! );
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+//! ```compile_fail
+//! # #[cfg(feature = "serde")]
+//! # fn main() {
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(serde)]
+//! #[serde(tag = "type")]
+//! enum Message {
+//!     Ping,
+//!     Pair(u8, u8), // error: #[serde(tag = "...")] cannot be used with tuple variants
+//! }
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # compile_error!("the `serde` argument requires the `serde` feature");
+//! ```
+//!
+//! # Variant arguments
+//! You can pass the following arguments to the `newtype_enum` attribute on enum variants:
+//!
+//! ## Skip the `Variant` implementation
+//! The variant is kept on the enum, but its type does not implement the [`Variant`](trait.Variant.html) trait. This allows multiple variants that wrap the same type.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum]
+//! enum Test {
+//!     #[newtype_enum(skip)]
+//!     Min(usize),
+//!     #[newtype_enum(skip)]
+//!     Max(usize),
+//!     Name(&'static str),
+//! }
+//!
+//! let test = Test::from_variant("Tester");
+//! assert_eq!(test.variant::<&str>(), Some(&"Tester"));
+//! assert!(!Test::Min(1).is_variant::<&str>());
+//! ```
+//!
+//! ## Wrap newtype variants
+//! The newtype variant is converted to a generated tuple struct, which gives the variant its own unique type. The generated struct implements `Deref`, `DerefMut` and `From` for the wrapped type.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum]
+//! enum Test {
+//!     #[newtype_enum(wrap)]
+//!     First(&'static str),
+//!     #[newtype_enum(wrap)]
+//!     Last(&'static str),
+//! }
+//!
+//! let test = Test::from_variant(Test_variants::First::from("Tester"));
+//! let first: &Test_variants::First = test.variant().unwrap();
+//! assert_eq!(first.len(), 6);
+//! ```
+//! ```
+//! enum Test {
+//!     First(Test_variants::First),
+//!     Last(Test_variants::Last),
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct First(pub(super) &'static str);
+//!
+//!     impl core::ops::Deref for First {
+//!         type Target = &'static str;
+//!         // ...
+//! #       fn deref(&self) -> &Self::Target {
+//! #           &self.0
+//! #       }
+//!     }
+//!
+//!     // impl DerefMut for First, impl From<&'static str> for First, ...
+//! #   pub(super) struct Last(pub(super) &'static str);
+//! }
+//! ```
+//!
+//! ## Rename the generated struct
+//! Set the name of the generated struct of the variant. This takes precedence over the `struct_prefix` and `struct_suffix` arguments. Newtype variants must be [wrapped](#wrap-newtype-variants) to be renamed.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum]
+//! enum Test {
+//!     #[newtype_enum(rename = "HelloMsg")]
+//!     Hello { name: &'static str },
+//!     #[newtype_enum(wrap, rename = "Count")]
+//!     Number(usize),
+//! }
+//!
+//! let test = Test::from_variant(Test_variants::HelloMsg { name: "Tester" });
+//! assert!(matches!(test, Test::Hello(_)));
+//! assert_eq!(
+//!     *Test::from_variant(Test_variants::Count::from(3))
+//!         .into_variant::<Test_variants::Count>()
+//!         .unwrap(),
+//!     3
+//! );
+//! ```
+//! ```
+//! enum Test {
+//!     Hello(Test_variants::HelloMsg),
+//!     Number(Test_variants::Count),
+//! }
+//!
+//! mod Test_variants {
+//!     pub(super) struct HelloMsg {
+//!         pub(super) name: &'static str,
+//!     }
+//!
+//!     pub(super) struct Count(pub(super) usize);
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(struct_suffix = "Msg")]
+//! enum Test {
+//!     #[newtype_enum(rename = "ExampleMsg")]
+//!     Hello,
+//!     Example, // error: the struct name `ExampleMsg` is already used by the variant `Hello`
+//! }
+//! ```
+//!
+//! ## Flatten nested enums
+//! The variant types of a nested newtype enum also implement [`Variant`](trait.Variant.html) for the outer enum. The conversions go through the flattened variant, so its `INDEX` is used and the `NAME` is qualified with its name (e.g. `Number::Float`).
+//! The nested variant types are not part of [`Enum::Variants`](trait.Enum.html#associatedtype.Variants) and [`Enum::VARIANT_NAMES`](trait.Enum.html#associatedconstant.VARIANT_NAMES) of the outer enum, which only list the flattened variant (e.g. `Number`).
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum]
+//! # #[derive(Debug, PartialEq)]
+//! enum Number {
+//!     Int(i64),
+//!     Float(f64),
+//! }
+//!
+//! #[newtype_enum]
+//! # #[derive(Debug, PartialEq)]
+//! enum Value {
+//!     Str(&'static str),
+//!     #[newtype_enum(flatten)]
+//!     Number(Number),
+//! }
+//!
+//! let value = Value::from_variant(1.5);
+//! assert_eq!(value, Value::Number(Number::Float(1.5)));
+//! assert_eq!(value.variant::<f64>(), Some(&1.5));
+//! assert_eq!(value.variant::<Number>(), Some(&Number::Float(1.5)));
+//! assert_eq!(value.variant::<i64>(), None);
+//!
+//! let err = value.try_into_variant::<i64>().unwrap_err();
+//! assert_eq!(err.expected(), "Number::Int");
+//! assert_eq!(err.found(), "Number");
+//! assert!(err
+//!     .to_string()
+//!     .starts_with("expected variant `Number::Int` of `"));
+//!
+//! use newtype_enum::Variant;
+//! assert_eq!(Value::VARIANT_NAMES, &["Str", "Number"]);
+//! assert_eq!(Value::VARIANT_NAMES[<f64 as Variant<Value>>::INDEX], "Number");
+//! ```
+//!
+//! The nested enum must be defined in the same crate and be named by its path (e.g. `inner::Number`), because a hidden macro next to the enum lists its variant types.
+//! The type of the flattened variant cannot use the generic parameters of the outer enum.
+//!
+//! ## Borrowed views
+//! [`Enum::variant_as`](trait.Enum.html#method.variant_as) returns a reference to a borrowed view of a variant, e.g. `&str` for a `String` variant.
+//! The views of `String`, `Vec<T>`, `Box<T>`, `Rc<T>`, `Arc<T>`, `Cow<'_, T>`, `PathBuf`, `OsString` and `CString` variants are implemented automatically (see [`VariantRef`](trait.VariantRef.html)), unless the view is used by multiple variants, is the type of another variant or uses a type parameter of the enum.
+//! The types must be named by their full path in `std` or `alloc` (e.g. `std::path::PathBuf`), only `String`, `Vec` and `Box` can also be named without a path.
+//! Use `no_borrow` to skip the automatic view, e.g. for a type from another crate that is imported as `String`.
+//! Other views can be set with the `borrow` argument, if the variant type implements `Borrow` for the view.
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! struct Name(String);
+//!
+//! impl core::borrow::Borrow<str> for Name {
+//!     fn borrow(&self) -> &str {
+//!         &self.0
+//!     }
 //! }
 //!
-//! mod test {
-//!     // <-- the name of the generated module
-//!     pub(super) struct Example;
+//! #[newtype_enum]
+//! enum Test {
+//!     #[newtype_enum(borrow = "str")]
+//!     Name(Name),
+//!     Bytes(Vec<u8>),
 //! }
+//!
+//! let test = Test::from_variant(Name("Tester".into()));
+//! assert_eq!(test.variant_as::<str>(), Some("Tester"));
+//! assert_eq!(test.variant_as::<[u8]>(), None);
 //! ```
-//! ## Variants module visibility
 //! ```
 //! # use newtype_enum::newtype_enum;
-//! #[newtype_enum(variants = "pub(crate) test")]
+//! mod my {
+//!     pub struct String;
+//! }
+//!
+//! mod other {
+//!     pub struct String;
+//! }
+//!
+//! use other::String;
+//!
+//! #[newtype_enum]
 //! enum Test {
-//!     Example,
+//!     Name(my::String),
+//!     #[newtype_enum(no_borrow)]
+//!     Other(String),
+//!     Path(std::path::PathBuf), // implements `VariantRef<Test>` for `std::path::Path`
 //! }
+//! # fn main() {}
 //! ```
-//! ```
+//!
+//! A view is not implemented automatically, if it is the type of another variant. Use [`Enum::variant`](trait.Enum.html#method.variant) for that variant instead:
+//! ```compile_fail
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum]
 //! enum Test {
-//!     Example(test::Example),
+//!     Boxed(Box<u8>),
+//!     Plain(u8),
 //! }
 //!
-//! pub(crate) mod test {
-//!     // <-- the visibility of the generated module
-//!     pub(super) struct Example;
-//! }
+//! let test = Test::from_variant(5u8);
+//! assert_eq!(test.variant::<u8>(), Some(&5));
+//! test.variant_as::<u8>(); // error: `u8` is the type of the `Plain` variant and not a view of the `Boxed` variant
 //! ```
 //!
 //! # Visibilities and attributes (e.g. `#[derive]` attributes)
@@ -113,7 +1215,7 @@
 //!
 //! Location | Destination
 //! -|-
-//! enum | Enum and generated variant structs
+//! enum | Enum and generated variant structs (except `#[doc]`, `#[repr]` and `#[non_exhaustive]`)
 //! enum variant | Generated variant struct
 //! variant field | Generated struct field
 //!
@@ -149,14 +1251,109 @@
 //! }
 //! # }
 //! ```
+//!
+//! Attributes can also be routed explicitly with the following arguments of the `newtype_enum` macro:
+//!
+//! Argument | Destination
+//! -|-
+//! `enum_attrs(...)` | Enum
+//! `variant_attrs(...)` | Generated variant structs
+//! `derive_variants(...)` | Generated variant structs (as `#[derive(...)]`)
+//!
+//! ```
+//! # use newtype_enum::newtype_enum;
+//! #[newtype_enum(enum_attrs(derive(PartialEq)), derive_variants(Clone, PartialEq))]
+//! #[derive(Debug)]
+//! enum Test {
+//!     Example { test: usize },
+//!     Number(usize),
+//! }
+//! ```
+//! ```
+//! #[derive(Debug, PartialEq)]
+//! enum Test {
+//!     Example(Test_variants::Example),
+//!     Number(usize),
+//! }
+//!
+//! mod Test_variants {
+//!     #[derive(Debug, Clone, PartialEq)]
+//!     pub(super) struct Example {
+//!         pub(super) test: usize,
+//!     }
+//! }
+//! ```
+//!
+//! `#[derive(Default)]` cannot be applied to the transformed enum, because all its variants have fields. Instead the macro implements `Default` with the default value of the `#[default]` variant.
+//! Only the generated struct of the `#[default]` variant derives `Default`, so the other variants can have fields without a default value:
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum]
+//! #[derive(Default)]
+//! enum Test {
+//!     #[default]
+//!     Empty,
+//!     Error {
+//!         error: std::io::Error,
+//!     }, // `std::io::Error` does not implement `Default`
+//!     Number(usize),
+//! }
+//!
+//! assert!(Test::default().is_variant::<Test_variants::Empty>());
+//! ```
+//!
+//! `Default` can also be derived with the `enum_attrs` argument:
+//! ```
+//! # use newtype_enum::{newtype_enum, Enum};
+//! #[newtype_enum(enum_attrs(derive(Default)))]
+//! enum Test {
+//!     #[default]
+//!     Empty,
+//!     Number(u32),
+//! }
+//!
+//! assert!(Test::default().is_variant::<Test_variants::Empty>());
+//! ```
+//!
+//! `#[serde]` attributes are an exception, because they depend on whether they are applied to an enum or a struct:
+//!
+//! Location | Attribute | Destination
+//! -|-|-
+//! enum | `rename_all_fields` | Generated variant structs (as `rename_all`)
+//! enum | `deny_unknown_fields` | Enum and generated variant structs
+//! enum | all others | Enum
+//! enum variant | `rename_all`, `deny_unknown_fields` | Generated variant struct
+//! enum variant | all others | Enum variant
+//!
+//! # Cargo features
+//! Feature | Description
+//! -|-
+//! `std` | Implement `std::error::Error` for [`VariantMismatch`](struct.VariantMismatch.html)
+//! `serde` | Enable the [`serde`](#serde) argument (requires the `alloc` crate)
+
+#[cfg(feature = "std")]
+extern crate std;
 
+pub mod hlist;
+pub mod matcher;
 pub mod unstable;
 
+use core::{
+    borrow::Borrow,
+    fmt::{self, Debug, Display, Formatter},
+    hash::Hash,
+};
+
 /// Define a newtype enum.
 ///
 /// See [crate-level documentation](index.html) for more information.
 pub use newtype_enum_macro::newtype_enum;
 
+/// Allow delegating the implementation of a trait to the variants of a newtype enum.
+///
+/// See [crate-level documentation](index.html#trait-delegation) for more information.
+pub use newtype_enum_macro::delegate;
+
 /// Mark a type as an `enum`.
 ///
 /// Use the [`newtype_enum`](attr.newtype_enum.html) macro to implement this trait for your enum types.
@@ -186,6 +1383,161 @@ pub use newtype_enum_macro::newtype_enum;
 /// assert_eq!(variant, cloned);
 /// ```
 pub trait Enum: Sized {
+    /// The number of variants of the enum.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # pub enum Test {
+    /// #     Ping,
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// # use newtype_enum::Enum;
+    /// assert_eq!(Test::VARIANT_COUNT, 3);
+    /// # }
+    /// ```
+    const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+
+    /// The names of all variants of the enum in declaration order.
+    ///
+    /// The variant types of a [flattened](index.html#flatten-nested-enums) enum are not listed,
+    /// their [`Variant::NAME`](trait.Variant.html#associatedconstant.NAME) is qualified with the name of the flattened variant instead.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # pub enum Test {
+    /// #     Ping,
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// # use newtype_enum::Enum;
+    /// assert_eq!(Test::VARIANT_NAMES, &["Ping", "Number", "Str"]);
+    /// # }
+    /// ```
+    const VARIANT_NAMES: &'static [&'static str];
+
+    /// The type-level list of all [`Variant`](trait.Variant.html) types of the enum in declaration order.
+    ///
+    /// Variants with `#[newtype_enum(skip)]` are not part of the list. The variant types of a
+    /// [flattened](index.html#flatten-nested-enums) enum are not part of the list either, only the type of the flattened variant is.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # pub enum Test {
+    /// #     Ping,
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// use core::any::TypeId;
+    /// use newtype_enum::{
+    ///     hlist::{HCons, HNil, VariantList},
+    ///     Enum,
+    /// };
+    ///
+    /// type Variants = HCons<Test_variants::Ping, HCons<usize, HCons<&'static str, HNil>>>;
+    /// assert_eq!(
+    ///     TypeId::of::<<Test as Enum>::Variants>(),
+    ///     TypeId::of::<Variants>()
+    /// );
+    /// assert_eq!(<Test as Enum>::Variants::LEN, 3);
+    /// # }
+    /// ```
+    type Variants: hlist::VariantList<Self>;
+
+    /// Get the index of the current variant in declaration order.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # pub enum Test {
+    /// #     Ping,
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// # use newtype_enum::Enum;
+    /// let test = Test::from_variant(123);
+    /// assert_eq!(test.variant_index(), 1);
+    /// # }
+    /// ```
+    fn variant_index(&self) -> usize;
+
+    /// Get the name of the current variant.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # pub enum Test {
+    /// #     Ping,
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// # use newtype_enum::Enum;
+    /// let test = Test::from_variant(123);
+    /// assert_eq!(test.variant_name(), "Number");
+    /// # }
+    /// ```
+    fn variant_name(&self) -> &'static str {
+        Self::VARIANT_NAMES[self.variant_index()]
+    }
+
+    /// Call a generic callback for every [`Variant`](trait.Variant.html) type of the enum in declaration order.
+    ///
+    /// The callback is called for the types in [`Variants`](#associatedtype.Variants), so the variant types of a flattened enum are skipped.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # pub enum Test {
+    /// #     Ping,
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// use newtype_enum::{hlist::VariantTypeVisitor, Enum, Variant};
+    ///
+    /// struct Names(Vec<&'static str>);
+    ///
+    /// impl VariantTypeVisitor<Test> for Names {
+    ///     fn visit<V: Variant<Test>>(&mut self) {
+    ///         self.0.push(V::NAME);
+    ///     }
+    /// }
+    ///
+    /// let mut names = Names(Vec::new());
+    /// Test::for_each_variant_type(&mut names);
+    /// assert_eq!(names.0, ["Ping", "Number", "Str"]);
+    /// # }
+    /// ```
+    fn for_each_variant_type<F: hlist::VariantTypeVisitor<Self> + ?Sized>(visitor: &mut F) {
+        <Self::Variants as hlist::VariantList<Self>>::for_each(visitor);
+    }
+
+    /// Create a [`Matcher`](matcher/struct.Matcher.html) to call one closure per variant type.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # pub enum Test {
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// use newtype_enum::Enum;
+    ///
+    /// let test = Test::from_variant("Hello World");
+    /// let len = test
+    ///     .matcher()
+    ///     .on::<usize, _>(|n| n)
+    ///     .on::<&str, _>(str::len)
+    ///     .finish();
+    /// assert_eq!(len, 11);
+    /// # }
+    /// ```
+    fn matcher<R>(self) -> matcher::Matcher<Self, R, Self::Variants> {
+        matcher::Matcher::new(self)
+    }
+
     /// Construct an enum from one of its newtype variants.
     ///
     /// ```
@@ -255,6 +1607,34 @@ pub trait Enum: Sized {
         V::from_enum(self)
     }
 
+    /// Convert the enum into one of its newtype variants or get the enum back if the variant does not match.
+    ///
+    /// # Errors
+    /// Returns a [`VariantMismatch`](struct.VariantMismatch.html) error, which contains the enum, if it does not hold the newtype variant `V`.
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # #[derive(Debug, PartialEq, Eq)]
+    /// # pub enum Test {
+    /// #     Number(usize),
+    /// #     Str(&'static str),
+    /// # }
+    /// # fn main() {
+    /// # use newtype_enum::Enum;
+    /// let test = Test::from_variant(123);
+    ///
+    /// let test = match test.try_into_variant::<&str>() {
+    ///     Ok(_) => panic!("expected a usize variant"),
+    ///     Err(err) => err.into_enum(),
+    /// };
+    ///
+    /// assert_eq!(test.try_into_variant().ok(), Some(123));
+    /// # }
+    /// ```
+    fn try_into_variant<V: Variant<Self>>(self) -> Result<V, VariantMismatch<Self>> {
+        V::try_from_enum(self).map_err(VariantMismatch::new::<V>)
+    }
+
     /// Get a reference to one of its newtype variants.
     ///
     /// ```
@@ -304,6 +1684,28 @@ pub trait Enum: Sized {
         V::mut_enum(self)
     }
 
+    /// Get a borrowed view of one of its newtype variants.
+    ///
+    /// The view `T` can be an unsized type, e.g. `str` for a `String` variant (see [`VariantRef`](trait.VariantRef.html)).
+    ///
+    /// ```
+    /// # #[newtype_enum::newtype_enum]
+    /// # #[derive(Debug, PartialEq, Eq)]
+    /// # pub enum Test {
+    /// #     Name(String),
+    /// #     Bytes(Vec<u8>),
+    /// # }
+    /// # fn main() {
+    /// # use newtype_enum::Enum;
+    /// let test = Test::from_variant(String::from("Tester"));
+    /// assert_eq!(test.variant_as::<str>(), Some("Tester"));
+    /// assert_eq!(test.variant_as::<[u8]>(), None);
+    /// # }
+    /// ```
+    fn variant_as<T: VariantRef<Self> + ?Sized>(&self) -> Option<&T> {
+        self.variant::<T::Variant>().map(Borrow::borrow)
+    }
+
     /// Check if the enum currently holds the newtype variant `V`.
     ///
     /// If this method returns `true`, it is safe to call one of the `variant_unchecked` methods.
@@ -456,4 +1858,189 @@ pub trait Enum: Sized {
 /// Mark a type as a newtype variant of an [`Enum`](trait.Enum.html) `E`.
 ///
 /// Use the [`newtype_enum`](attr.newtype_enum.html) macro to implement this trait for your enum variants.
-pub trait Variant<E: Enum>: unstable::VariantCore<E> {}
+///
+/// ```
+/// # #[newtype_enum::newtype_enum]
+/// # pub enum Test {
+/// #     Ping,
+/// #     Number(usize),
+/// #     Str(&'static str),
+/// # }
+/// # fn main() {
+/// use newtype_enum::Variant;
+///
+/// assert_eq!(<&str as Variant<Test>>::NAME, "Str");
+/// assert_eq!(<&str as Variant<Test>>::INDEX, 2);
+/// # }
+/// ```
+pub trait Variant<E: Enum>: unstable::VariantCore<E> {
+    /// The name of the variant.
+    ///
+    /// The name of a variant type of a [flattened](index.html#flatten-nested-enums) enum is qualified with the name of the flattened variant (e.g. `Number::Float`).
+    const NAME: &'static str;
+
+    /// The index of the variant in declaration order.
+    ///
+    /// A variant type of a [flattened](index.html#flatten-nested-enums) enum has the index of the flattened variant,
+    /// so `E::VARIANT_NAMES[V::INDEX]` is the name of the flattened variant and not `V::NAME`.
+    const INDEX: usize;
+}
+
+/// A borrowed view of a newtype [`Variant`](trait.Variant.html) of an [`Enum`](trait.Enum.html) `E`.
+///
+/// This trait is implemented for the (possibly unsized) view type instead of the variant type,
+/// so [`Enum::variant_as`](trait.Enum.html#method.variant_as) can infer the variant from the view.
+///
+/// Use the [`newtype_enum`](attr.newtype_enum.html) macro to implement this trait for the views of your enum variants:
+/// It is implemented for `String`, `Vec<T>`, `Box<T>`, `Rc<T>`, `Arc<T>`, `Cow<'_, T>`, `PathBuf`, `OsString` and `CString` variants
+/// or with the [`borrow`](index.html#borrowed-views) argument.
+///
+/// ```
+/// # #[newtype_enum::newtype_enum]
+/// # pub enum Test {
+/// #     Ping,
+/// #     Number(usize),
+/// #     Name(String),
+/// # }
+/// # fn main() {
+/// use newtype_enum::{Variant, VariantRef};
+///
+/// assert_eq!(
+///     <<str as VariantRef<Test>>::Variant as Variant<Test>>::NAME,
+///     "Name"
+/// );
+/// # }
+/// ```
+pub trait VariantRef<E: Enum> {
+    /// The newtype variant, which can be borrowed as this type.
+    type Variant: Variant<E> + Borrow<Self>;
+}
+
+/// An [`Enum`](trait.Enum.html) with a generated fieldless companion enum of its variant kinds.
+///
+/// Use the `kind` argument of the [`newtype_enum`](attr.newtype_enum.html) macro to implement this trait for your enum types.
+///
+/// ```
+/// # #[newtype_enum::newtype_enum(kind = "TestKind")]
+/// # pub enum Test {
+/// #     Ping,
+/// #     Number(usize),
+/// #     Str(&'static str),
+/// # }
+/// # fn main() {
+/// use newtype_enum::{Enum, EnumKind};
+///
+/// let test = Test::from_variant(123);
+/// assert_eq!(test.kind(), TestKind::Number);
+/// # }
+/// ```
+pub trait EnumKind: Enum {
+    /// The fieldless enum with one variant per variant of this enum.
+    type Kind: Copy + Eq + Ord + Hash + Debug;
+
+    /// Get the kind of the current variant.
+    fn kind(&self) -> Self::Kind;
+}
+
+/// A [`Variant`](trait.Variant.html) of an [`EnumKind`](trait.EnumKind.html) `E`.
+///
+/// Use the `kind` argument of the [`newtype_enum`](attr.newtype_enum.html) macro to implement this trait for your enum variants.
+///
+/// ```
+/// # #[newtype_enum::newtype_enum(kind = "TestKind")]
+/// # pub enum Test {
+/// #     Ping,
+/// #     Number(usize),
+/// #     Str(&'static str),
+/// # }
+/// # fn main() {
+/// use newtype_enum::VariantKind;
+///
+/// assert_eq!(<usize as VariantKind<Test>>::KIND, TestKind::Number);
+/// # }
+/// ```
+pub trait VariantKind<E: EnumKind>: Variant<E> {
+    /// The kind of this variant.
+    const KIND: E::Kind;
+}
+
+/// The error of a conversion from an [`Enum`](trait.Enum.html) into a [`Variant`](trait.Variant.html), which is not the current variant of the enum.
+///
+/// The original enum can be recovered with [`into_enum`](#method.into_enum).
+///
+/// This type implements `Display` and, with the `std` feature enabled, `std::error::Error`.
+///
+/// ```
+/// # #[newtype_enum::newtype_enum(try_from)]
+/// # #[derive(Debug, PartialEq, Eq)]
+/// # pub enum Test {
+/// #     Number(usize),
+/// #     Str(&'static str),
+/// # }
+/// # fn main() {
+/// use core::convert::TryFrom;
+///
+/// let err = usize::try_from(Test::Str("Hello World")).unwrap_err();
+/// assert_eq!(err.expected(), "Number");
+/// assert_eq!(err.found(), "Str");
+/// assert!(err
+///     .to_string()
+///     .starts_with("expected variant `Number` of `"));
+/// assert_eq!(err.into_enum(), Test::Str("Hello World"));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantMismatch<E> {
+    e: E,
+    expected: &'static str,
+    found: &'static str,
+}
+
+impl<E: Enum> VariantMismatch<E> {
+    /// Create a new error for an enum that was expected to hold the variant `V`.
+    pub fn new<V: Variant<E>>(e: E) -> Self {
+        let found = e.variant_name();
+        Self {
+            e,
+            expected: V::NAME,
+            found,
+        }
+    }
+}
+
+impl<E> Display for VariantMismatch<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected variant `{}` of `{}`, found variant `{}`",
+            self.expected,
+            core::any::type_name::<E>(),
+            self.found,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Debug> std::error::Error for VariantMismatch<E> {}
+
+impl<E> VariantMismatch<E> {
+    /// Get the name of the expected variant.
+    pub const fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// Get the name of the variant the enum holds.
+    pub const fn found(&self) -> &'static str {
+        self.found
+    }
+
+    /// Get a reference to the original enum.
+    pub const fn as_enum(&self) -> &E {
+        &self.e
+    }
+
+    /// Get the original enum back.
+    pub fn into_enum(self) -> E {
+        self.e
+    }
+}
@@ -0,0 +1,154 @@
+//! An exhaustive `match` builder with one closure per variant.
+
+use crate::{
+    hlist::{HNil, Pluck},
+    Enum, Variant,
+};
+use core::marker::PhantomData;
+
+/// Call one closure per variant of the enum `E`, which is checked for exhaustiveness at compile time.
+///
+/// `L` is the [type-level list](../hlist/index.html) of the variant types without a closure.
+/// Use [`Enum::matcher`](../trait.Enum.html#method.matcher) to create a matcher, add a closure for every variant type with [`on`](#method.on) (in any order)
+/// and call [`finish`](#method.finish) after all variants are handled.
+///
+/// The second type argument of `on` is the position of the variant type in the list, which is inferred by the compiler.
+///
+/// ```
+/// # #[newtype_enum::newtype_enum]
+/// # pub enum Test {
+/// #     Ping,
+/// #     Number(usize),
+/// #     Str(&'static str),
+/// # }
+/// # fn main() {
+/// use newtype_enum::Enum;
+///
+/// let test = Test::from_variant(123);
+/// let number = test
+///     .matcher()
+///     .on::<&str, _>(|s| s.len())
+///     .on(|n: usize| n)
+///     .on::<Test_variants::Ping, _>(|_| 0)
+///     .finish();
+/// assert_eq!(number, 123);
+/// # }
+/// ```
+///
+/// Missing variants are a compile error:
+///
+/// ```compile_fail
+/// # #[newtype_enum::newtype_enum]
+/// # pub enum Test {
+/// #     Ping,
+/// #     Number(usize),
+/// #     Str(&'static str),
+/// # }
+/// # fn main() {
+/// # use newtype_enum::Enum;
+/// let test = Test::from_variant(123);
+/// let number = test
+///     .matcher()
+///     .on::<&str, _>(|s| s.len())
+///     .on::<usize, _>(|n| n)
+///     .finish();
+/// # }
+/// ```
+#[must_use = "call `finish` to get the result of the closure"]
+pub struct Matcher<E, R, L> {
+    state: Result<R, E>,
+    variants: PhantomData<fn() -> L>,
+}
+
+impl<E: Enum, R, L> Matcher<E, R, L> {
+    pub(crate) const fn new(e: E) -> Self {
+        Self {
+            state: Err(e),
+            variants: PhantomData,
+        }
+    }
+
+    /// Call the closure if the enum is the variant `V`.
+    ///
+    /// `I` is the type-level index of `V` in the list of remaining variant types. It is inferred by the compiler.
+    pub fn on<V, I>(self, f: impl FnOnce(V) -> R) -> Matcher<E, R, <L as Pluck<V, I>>::Rest>
+    where
+        V: Variant<E>,
+        L: Pluck<V, I>,
+    {
+        let state = match self.state {
+            Err(e) => V::try_from_enum(e).map(f),
+            Ok(r) => Ok(r),
+        };
+        Matcher {
+            state,
+            variants: PhantomData,
+        }
+    }
+}
+
+impl<E: Enum, R> Matcher<E, R, HNil> {
+    /// Get the result of the closure of the current variant.
+    ///
+    /// This method is only available for enums without skipped variants (see [`NoSkippedVariants`](trait.NoSkippedVariants.html)).
+    /// Use [`try_finish`](#method.try_finish) otherwise.
+    ///
+    /// ```compile_fail
+    /// # use newtype_enum::newtype_enum;
+    /// #[newtype_enum]
+    /// enum Test {
+    ///     Number(usize),
+    ///     #[newtype_enum(skip)]
+    ///     Max(usize),
+    /// }
+    ///
+    /// # fn main() {
+    /// use newtype_enum::Enum;
+    ///
+    /// let number = Test::Max(42).matcher().on(|n: usize| n).finish(); // error: `Test` has skipped variants
+    /// # }
+    /// ```
+    pub fn finish(self) -> R
+    where
+        E: NoSkippedVariants,
+    {
+        let Ok(r) = self.state else {
+            unreachable!("every variant has a closure")
+        };
+        r
+    }
+
+    /// Get the result of the closure of the current variant.
+    ///
+    /// # Errors
+    /// Returns the enum if the current variant is skipped with `#[newtype_enum(skip)]`, because skipped variants have no closure.
+    ///
+    /// ```
+    /// # use newtype_enum::newtype_enum;
+    /// #[newtype_enum]
+    /// enum Test {
+    ///     Number(usize),
+    ///     #[newtype_enum(skip)]
+    ///     Max(usize),
+    /// }
+    ///
+    /// # fn main() {
+    /// use newtype_enum::Enum;
+    ///
+    /// let number = Test::Number(123).matcher().on(|n: usize| n).try_finish();
+    /// assert_eq!(number.ok(), Some(123));
+    ///
+    /// let max = Test::Max(42).matcher().on(|n: usize| n).try_finish();
+    /// assert!(matches!(max, Err(Test::Max(42))));
+    /// # }
+    /// ```
+    pub fn try_finish(self) -> Result<R, E> {
+        self.state
+    }
+}
+
+/// An [`Enum`](../trait.Enum.html) without variants that are skipped with `#[newtype_enum(skip)]`.
+///
+/// The [`newtype_enum`](../attr.newtype_enum.html) macro implements this trait, if all variants implement [`Variant`](../trait.Variant.html).
+/// A [`Matcher`](struct.Matcher.html) of these enums can be finished without an error.
+pub trait NoSkippedVariants: Enum {}
@@ -4,6 +4,13 @@
 
 use crate::Enum;
 
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+
+#[doc(hidden)]
+pub use newtype_enum_macro::{delegate_impl, flatten_impl};
+
 /// Mark a type as a newtype variant of an [`Enum`](../trait.Enum.html) `E`.
 ///
 /// Use the [`newtype_enum`](../attr.newtype_enum.html) macro to implement this trait for your enum variants.
@@ -16,6 +23,12 @@ pub trait VariantCore<E: Enum>: Sized {
     /// Convert an enum into this newtype variant.
     fn from_enum(e: E) -> Option<Self>;
 
+    /// Convert an enum into this newtype variant.
+    ///
+    /// # Errors
+    /// Returns the enum if it does not hold this newtype variant.
+    fn try_from_enum(e: E) -> Result<Self, E>;
+
     /// Get a reference to this this newtype variant.
     fn ref_enum(e: &E) -> Option<&Self>;
 
@@ -35,8 +48,17 @@ pub trait VariantCore<E: Enum>: Sized {
     ///
     /// Implementors **should** write this method without an intermediate `Option<V>` value.
     /// This sometimes allows the compiler to optimize the code better.
-    fn from_enum_unwrap(e: E) -> Self {
-        Self::from_enum(e).expect("called `Variant::from_enum_unwrap` on another enum variant")
+    fn from_enum_unwrap(e: E) -> Self
+    where
+        Self: crate::Variant<E>,
+    {
+        match Self::try_from_enum(e) {
+            Ok(v) => v,
+            Err(e) => panic!(
+                "called `Enum::into_variant_unwrap` on another enum variant: {}",
+                crate::VariantMismatch::new::<Self>(e),
+            ),
+        }
     }
 
     /// Convert an enum into this newtype variant.
@@ -54,3 +76,11 @@ pub trait VariantCore<E: Enum>: Sized {
         Self::mut_enum(e).unwrap_unchecked()
     }
 }
+
+/// Get the type of the newtype variant with the index `INDEX` of an [`Enum`](../trait.Enum.html).
+///
+/// The [`newtype_enum`](../attr.newtype_enum.html) macro implements this trait for every variant with a [`Variant`](../trait.Variant.html) implementation.
+pub trait VariantAt<const INDEX: usize>: Enum {
+    /// The type of the newtype variant.
+    type Type: crate::Variant<Self>;
+}